@@ -1,19 +1,66 @@
-var dingSound;
-var bowlSound;
+var intermediateSound;
+var finalSound;
+var previewSound;
 
-export function setMedia() {
-  dingSound = new Media('/android_asset/www/assets/sounds/ding.ogg');
-  bowlSound = new Media('/android_asset/www/assets/sounds/bowl.ogg');
+// `setRate` is not available on every platform, so pitch variations
+// of a recording may sound like the recording itself
+function newMedia(file, rate) {
+  const media = new Media(file);
+  if (media.setRate) {
+    media.setRate(rate);
+  }
+  return media;
 }
 
-export function playDing(volume) {
-  dingSound.play();
-  dingSound.setVolume(volume);
+export function setMedia(intermediateFile, intermediateRate, finalFile, finalRate) {
+  if (intermediateSound) {
+    intermediateSound.release();
+    finalSound.release();
+  }
+  intermediateSound = newMedia(intermediateFile, intermediateRate);
+  finalSound = newMedia(finalFile, finalRate);
 }
 
-export function playBowl(volume) {
-  bowlSound.play();
-  bowlSound.setVolume(volume);
+export function playIntermediate(volume) {
+  intermediateSound.play();
+  intermediateSound.setVolume(volume);
+}
+
+export function playFinal(volume) {
+  finalSound.play();
+  finalSound.setVolume(volume);
+}
+
+export function playPreview(file, rate, volume) {
+  if (previewSound) {
+    previewSound.stop();
+    previewSound.release();
+  }
+  previewSound = newMedia(file, rate);
+  previewSound.play();
+  previewSound.setVolume(volume);
+}
+
+// Reports time from a play request until the player runs
+// with a "latency" event carrying the given id
+export function measureLatency(id, file, rate, volume) {
+  let start;
+  const media = new Media(file, null, null, (status) => {
+    if (status === Media.MEDIA_RUNNING) {
+      const latency = performance.now() - start;
+      document.dispatchEvent(new CustomEvent("latency", { detail: { id, latency } }));
+      setTimeout(() => {
+        media.stop();
+        media.release();
+      }, 1000);
+    }
+  });
+  if (media.setRate) {
+    media.setRate(rate);
+  }
+  start = performance.now();
+  media.play();
+  media.setVolume(volume);
 }
 
 export function startForegroundService() {
@@ -8,8 +8,6 @@ function onDeviceReady() {
   // This is required(?) for background service to work with Android 12+
   cordova.plugins.backgroundMode.setDefaults({ silent: true });
 
-  aux.setMedia();
-
   (async () => {
       const { default: init, main } = await import("./attention_challenge.js");
       init().then(() => {
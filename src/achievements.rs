@@ -0,0 +1,235 @@
+use std::collections::BTreeMap;
+
+use crate::db::SavedSession;
+
+/// Condition an achievement is unlocked by
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Condition {
+    /// Total number of sessions
+    Sessions(usize),
+    /// Consecutive days with sessions
+    Streak(usize),
+    /// Total practice time in minutes
+    Minutes(usize),
+    /// A session rated at least that score
+    Score(usize),
+    /// Number of sessions in one day
+    DailySessions(usize),
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Achievement {
+    /// Key an unlock date is saved under
+    pub id: &'static str,
+    pub title: &'static str,
+    pub description: &'static str,
+    pub condition: Condition,
+}
+
+/// All achievements in the order they are listed
+pub const ACHIEVEMENTS: &[Achievement] = &[
+    Achievement {
+        id: "first-session",
+        title: "First Step",
+        description: "Complete your first session",
+        condition: Condition::Sessions(1),
+    },
+    Achievement {
+        id: "perfect-score",
+        title: "Perfect Attention",
+        description: "Rate a session 5/5",
+        condition: Condition::Score(5),
+    },
+    Achievement {
+        id: "three-a-day",
+        title: "Dedicated Day",
+        description: "Complete 3 sessions in one day",
+        condition: Condition::DailySessions(3),
+    },
+    Achievement {
+        id: "streak-7",
+        title: "Week of Practice",
+        description: "Practise 7 days in a row",
+        condition: Condition::Streak(7),
+    },
+    Achievement {
+        id: "streak-30",
+        title: "Month of Practice",
+        description: "Practise 30 days in a row",
+        condition: Condition::Streak(30),
+    },
+    Achievement {
+        id: "sessions-10",
+        title: "Getting Started",
+        description: "Complete 10 sessions",
+        condition: Condition::Sessions(10),
+    },
+    Achievement {
+        id: "sessions-100",
+        title: "Centurion",
+        description: "Complete 100 sessions",
+        condition: Condition::Sessions(100),
+    },
+    Achievement {
+        id: "hours-10",
+        title: "Ten Hours",
+        description: "Practise for 10 hours in total",
+        condition: Condition::Minutes(10 * 60),
+    },
+];
+
+/// Running figures of a session log replayed in chronological order
+#[derive(Debug, Default)]
+struct Tally {
+    sessions: usize,
+    minutes: usize,
+    best_score: usize,
+    streak: usize,
+    day_sessions: usize,
+    last_day: Option<i64>,
+}
+
+impl Tally {
+    fn add(&mut self, session: &SavedSession, day: i64) {
+        self.sessions += 1;
+        self.minutes += session.duration;
+        self.best_score = self.best_score.max(session.score);
+        match self.last_day {
+            Some(last) if last == day => {
+                self.day_sessions += 1;
+            }
+            Some(last) if last + 1 == day => {
+                self.streak += 1;
+                self.day_sessions = 1;
+            }
+            _ => {
+                self.streak = 1;
+                self.day_sessions = 1;
+            }
+        }
+        self.last_day = Some(day);
+    }
+}
+
+impl Condition {
+    fn is_met(&self, tally: &Tally) -> bool {
+        match *self {
+            Self::Sessions(count) => tally.sessions >= count,
+            Self::Streak(days) => tally.streak >= days,
+            Self::Minutes(minutes) => tally.minutes >= minutes,
+            Self::Score(score) => tally.sessions > 0 && tally.best_score >= score,
+            Self::DailySessions(count) => tally.day_sessions >= count,
+        }
+    }
+}
+
+/// Replays sessions in chronological order and returns day numbers
+/// when achievements were unlocked by their ids.
+pub fn unlocks(sessions: &[SavedSession], day_start: u32) -> BTreeMap<&'static str, i64> {
+    let mut sessions: Vec<&SavedSession> = sessions.iter().collect();
+    sessions.sort_by_key(|s| s.ts.parse::<u64>().unwrap_or(0));
+
+    let mut unlocked = BTreeMap::new();
+    let mut tally = Tally::default();
+    for session in sessions {
+        let day = session.day(day_start);
+        tally.add(session, day);
+        for achievement in ACHIEVEMENTS {
+            if !unlocked.contains_key(achievement.id) && achievement.condition.is_met(&tally) {
+                unlocked.insert(achievement.id, day);
+            }
+        }
+    }
+
+    unlocked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{session, MONDAY};
+
+    #[test]
+    fn no_sessions_unlock_nothing() {
+        assert!(unlocks(&[], 0).is_empty());
+    }
+
+    #[test]
+    fn first_session_and_perfect_score() {
+        let sessions = vec![
+            session(MONDAY, 8, 15, 3),
+            session(MONDAY + 2, 8, 15, 5),
+        ];
+        let unlocked = unlocks(&sessions, 0);
+        assert_eq!(unlocked.get("first-session"), Some(&MONDAY));
+        assert_eq!(unlocked.get("perfect-score"), Some(&(MONDAY + 2)));
+        assert_eq!(unlocked.get("streak-7"), None);
+    }
+
+    #[test]
+    fn unlock_order_follows_time_not_storage_order() {
+        let sessions = vec![
+            session(MONDAY + 1, 8, 15, 5),
+            session(MONDAY, 8, 15, 2),
+        ];
+        let unlocked = unlocks(&sessions, 0);
+        assert_eq!(unlocked.get("first-session"), Some(&MONDAY));
+        assert_eq!(unlocked.get("perfect-score"), Some(&(MONDAY + 1)));
+    }
+
+    #[test]
+    fn streak_counts_consecutive_days() {
+        let mut sessions: Vec<SavedSession> = (0..6)
+            .map(|i| session(MONDAY + i, 8, 15, 3))
+            .collect();
+        // A gap restarts the streak
+        sessions.push(session(MONDAY + 7, 8, 15, 3));
+        assert_eq!(unlocks(&sessions, 0).get("streak-7"), None);
+
+        for i in 8..14 {
+            sessions.push(session(MONDAY + i, 8, 15, 3));
+        }
+        assert_eq!(unlocks(&sessions, 0).get("streak-7"), Some(&(MONDAY + 13)));
+    }
+
+    #[test]
+    fn several_sessions_a_day_keep_streak() {
+        let sessions: Vec<SavedSession> = (0..7)
+            .flat_map(|i| [session(MONDAY + i, 8, 15, 3), session(MONDAY + i, 20, 15, 3)])
+            .collect();
+        assert_eq!(unlocks(&sessions, 0).get("streak-7"), Some(&(MONDAY + 6)));
+    }
+
+    #[test]
+    fn daily_sessions_respect_day_start() {
+        // 01:00 belongs to the previous day when a day starts at 02:00
+        let sessions = vec![
+            session(MONDAY, 8, 15, 3),
+            session(MONDAY + 1, 1, 15, 3),
+            session(MONDAY + 1, 8, 15, 3),
+            session(MONDAY + 1, 20, 15, 3),
+        ];
+        assert_eq!(unlocks(&sessions, 0).get("three-a-day"), Some(&(MONDAY + 1)));
+        assert_eq!(unlocks(&sessions, 2 * 60).get("three-a-day"), None);
+
+        let sessions = vec![
+            session(MONDAY, 8, 15, 3),
+            session(MONDAY, 20, 15, 3),
+            session(MONDAY + 1, 1, 15, 3),
+        ];
+        assert_eq!(unlocks(&sessions, 0).get("three-a-day"), None);
+        assert_eq!(unlocks(&sessions, 2 * 60).get("three-a-day"), Some(&MONDAY));
+    }
+
+    #[test]
+    fn totals() {
+        let sessions: Vec<SavedSession> = (0..100)
+            .map(|i| session(MONDAY + i, 8, 6, 3))
+            .collect();
+        let unlocked = unlocks(&sessions, 0);
+        assert_eq!(unlocked.get("sessions-10"), Some(&(MONDAY + 9)));
+        assert_eq!(unlocked.get("sessions-100"), Some(&(MONDAY + 99)));
+        assert_eq!(unlocked.get("hours-10"), Some(&(MONDAY + 99)));
+        assert_eq!(unlocked.get("streak-30"), Some(&(MONDAY + 29)));
+    }
+}
@@ -0,0 +1,344 @@
+use std::collections::BTreeMap;
+
+use crate::calendar::weekday;
+use crate::db::SavedSession;
+use crate::stats::{PeriodAvg, TIME_SLOT_HOURS};
+
+/// Number of days in a month used to express trend slopes
+pub const DAYS_IN_MONTH: f32 = 30.0;
+
+/// Least-squares line `avg = slope * day + intercept`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Trend {
+    /// Score change per day
+    pub slope: f32,
+    pub intercept: f32,
+}
+
+impl Trend {
+    pub fn value_at(&self, day: f64) -> f64 {
+        self.slope as f64 * day + self.intercept as f64
+    }
+
+    /// Score change per month
+    pub fn slope_per_month(&self) -> f32 {
+        self.slope * DAYS_IN_MONTH
+    }
+}
+
+/// Simple linear regression `y = slope * x + intercept`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Regression {
+    pub slope: f32,
+    pub intercept: f32,
+    /// Sample size
+    pub count: usize,
+}
+
+/// Mean score of sessions sharing some property
+#[derive(Clone, Debug, PartialEq)]
+pub struct GroupMean {
+    pub label: String,
+    pub mean: f32,
+    /// Sample size
+    pub count: usize,
+}
+
+/// Score against session parameters
+#[derive(Debug, Default, PartialEq)]
+pub struct Correlations {
+    pub duration: Vec<GroupMean>,
+    pub delay: Vec<GroupMean>,
+    pub time_of_day: Vec<GroupMean>,
+    pub weekday: Vec<GroupMean>,
+    pub tags: Vec<GroupMean>,
+    /// Score against duration in minutes
+    pub duration_regression: Option<Regression>,
+    /// Score against delay in minutes
+    pub delay_regression: Option<Regression>,
+}
+
+const WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+/// Width of duration and delay groups in minutes
+const MINUTES_GROUP: usize = 5;
+
+/// Computes grouped means and regressions of score against session parameters.
+pub fn correlations(sessions: &[SavedSession], day_start: u32) -> Correlations {
+    let minutes_label = |minutes: usize| {
+        let start = minutes / MINUTES_GROUP * MINUTES_GROUP;
+        format!("{}–{}", start, start + MINUTES_GROUP - 1)
+    };
+    let duration = grouped_means(sessions, |s| {
+        Some((s.duration / MINUTES_GROUP, minutes_label(s.duration)))
+    });
+    let delay = grouped_means(sessions, |s| {
+        s.delay.map(|delay| (delay / MINUTES_GROUP, minutes_label(delay)))
+    });
+    let time_of_day = grouped_means(sessions, |s| {
+        let slot = s.minute_of_day() / 60 / TIME_SLOT_HOURS * TIME_SLOT_HOURS;
+        Some((slot as usize, format!("{slot:02}")))
+    });
+    let weekday = grouped_means(sessions, |s| {
+        let day = weekday(s.day(day_start)) as usize;
+        Some((day, WEEKDAYS[day].to_string()))
+    });
+
+    let mut tags: BTreeMap<&str, Vec<usize>> = BTreeMap::new();
+    for session in sessions {
+        for tag in &session.tags {
+            tags.entry(tag).or_default().push(session.score);
+        }
+    }
+    let tags = tags
+        .into_iter()
+        .map(|(tag, scores)| GroupMean {
+            label: tag.to_string(),
+            mean: scores.iter().sum::<usize>() as f32 / scores.len() as f32,
+            count: scores.len(),
+        })
+        .collect();
+
+    let duration_regression = regression(
+        &sessions
+            .iter()
+            .map(|s| (s.duration as f64, s.score as f64))
+            .collect::<Vec<_>>()
+    );
+    let delay_regression = regression(
+        &sessions
+            .iter()
+            .filter_map(|s| s.delay.map(|delay| (delay as f64, s.score as f64)))
+            .collect::<Vec<_>>()
+    );
+
+    Correlations {
+        duration,
+        delay,
+        time_of_day,
+        weekday,
+        tags,
+        duration_regression,
+        delay_regression,
+    }
+}
+
+/// Computes mean scores of groups in order of their keys.
+/// `group` returns a key and a label of a session group (or `None` to skip it).
+fn grouped_means<F>(sessions: &[SavedSession], group: F) -> Vec<GroupMean>
+where
+    F: Fn(&SavedSession) -> Option<(usize, String)>,
+{
+    let mut groups: BTreeMap<usize, (String, usize, usize)> = BTreeMap::new();
+    for session in sessions {
+        if let Some((key, label)) = group(session) {
+            let entry = groups.entry(key).or_insert((label, 0, 0));
+            entry.1 += session.score;
+            entry.2 += 1;
+        }
+    }
+
+    groups
+        .into_values()
+        .map(|(label, total, count)| GroupMean {
+            label,
+            mean: total as f32 / count as f32,
+            count,
+        })
+        .collect()
+}
+
+/// Fits a least-squares line through points.
+/// Returns `None` if there are less than two distinct `x` values.
+pub fn regression(points: &[(f64, f64)]) -> Option<Regression> {
+    if points.len() < 2 {
+        return None;
+    }
+    let n = points.len() as f64;
+    // Center values to keep precision
+    let x_mean = points.iter().map(|p| p.0).sum::<f64>() / n;
+    let y_mean = points.iter().map(|p| p.1).sum::<f64>() / n;
+    let mut covariance = 0.0;
+    let mut variance = 0.0;
+    for (x, y) in points {
+        let dx = x - x_mean;
+        covariance += dx * (y - y_mean);
+        variance += dx * dx;
+    }
+    if variance == 0.0 {
+        return None;
+    }
+    let slope = covariance / variance;
+
+    Some(Regression {
+        slope: slope as f32,
+        intercept: (y_mean - slope * x_mean) as f32,
+        count: points.len(),
+    })
+}
+
+/// Computes a mean of period averages within a window of `window` days
+/// (ending with a period) for every period with data.
+pub fn rolling_mean(points: &[PeriodAvg], window: i64) -> Vec<(i64, f32)> {
+    let mut start = 0;
+    let mut sum = 0.0;
+    points
+        .iter()
+        .enumerate()
+        .map(|(i, point)| {
+            sum += point.avg;
+            while points[start].day <= point.day - window {
+                sum -= points[start].avg;
+                start += 1;
+            }
+            (point.day, sum / (i + 1 - start) as f32)
+        })
+        .collect()
+}
+
+/// Computes an exponentially weighted moving average. `alpha` is a weight of
+/// a new value after one period of `period_days` days, so longer gaps give
+/// new values more weight.
+pub fn ewma(points: &[PeriodAvg], alpha: f32, period_days: i64) -> Vec<(i64, f32)> {
+    let mut result: Vec<(i64, f32)> = Vec::with_capacity(points.len());
+    for point in points {
+        let value = match result.last() {
+            Some((day, previous)) => {
+                let periods = ((point.day - day) as f32 / period_days as f32).round().max(1.0);
+                let weight = 1.0 - (1.0 - alpha).powf(periods);
+                previous + weight * (point.avg - previous)
+            }
+            None => point.avg,
+        };
+        result.push((point.day, value));
+    }
+
+    result
+}
+
+/// Fits a least-squares line through period averages.
+/// Returns `None` if there are less than two periods.
+pub fn linear_trend(points: &[PeriodAvg]) -> Option<Trend> {
+    let points: Vec<(f64, f64)> = points
+        .iter()
+        .map(|p| (p.day as f64, p.avg as f64))
+        .collect();
+
+    regression(&points).map(|r| Trend { slope: r.slope, intercept: r.intercept })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{self, MONDAY};
+
+    fn point(day: i64, avg: f32) -> PeriodAvg {
+        PeriodAvg { day, avg, count: 1, min: 0, max: 5 }
+    }
+
+    fn session(day: i64, hour: i64, duration: usize, delay: usize, score: usize) -> SavedSession {
+        SavedSession {
+            delay: Some(delay),
+            ..test_util::session(day, hour, duration, score)
+        }
+    }
+
+    fn assert_close(a: f32, b: f32) {
+        assert!((a - b).abs() < 1e-4, "{a} != {b}");
+    }
+
+    #[test]
+    fn rolling_mean_of_empty_and_single() {
+        assert!(rolling_mean(&[], 7).is_empty());
+        assert_eq!(rolling_mean(&[point(MONDAY, 3.0)], 7), vec![(MONDAY, 3.0)]);
+    }
+
+    #[test]
+    fn rolling_mean_window_edges() {
+        let points = [point(0, 1.0), point(6, 3.0), point(7, 5.0), point(20, 2.0)];
+        assert_eq!(rolling_mean(&points, 7), vec![
+            // Day 0 is still within a week ending with day 6
+            (0, 1.0),
+            (6, 2.0),
+            // but not within a week ending with day 7
+            (7, 4.0),
+            // A window may contain only its last point
+            (20, 2.0),
+        ]);
+    }
+
+    #[test]
+    fn ewma_of_empty_and_single() {
+        assert!(ewma(&[], 0.5, 1).is_empty());
+        assert_eq!(ewma(&[point(MONDAY, 3.0)], 0.5, 1), vec![(MONDAY, 3.0)]);
+    }
+
+    #[test]
+    fn ewma_weights_gaps() {
+        let result = ewma(&[point(0, 0.0), point(1, 4.0), point(3, 0.0)], 0.5, 1);
+        assert_close(result[1].1, 2.0);
+        // Two periods later a new value weighs 1 - 0.5²
+        assert_close(result[2].1, 0.5);
+        // Weekly periods
+        let result = ewma(&[point(0, 0.0), point(7, 4.0)], 0.5, 7);
+        assert_close(result[1].1, 2.0);
+    }
+
+    #[test]
+    fn regression_needs_distinct_points() {
+        assert_eq!(regression(&[]), None);
+        assert_eq!(regression(&[(1.0, 2.0)]), None);
+        assert_eq!(regression(&[(1.0, 2.0), (1.0, 4.0)]), None);
+    }
+
+    #[test]
+    fn regression_fits_a_line() {
+        let points: Vec<(f64, f64)> = (0..5).map(|x| (x as f64, 2.0 * x as f64 + 1.0)).collect();
+        let line = regression(&points).unwrap();
+        assert_close(line.slope, 2.0);
+        assert_close(line.intercept, 1.0);
+        assert_eq!(line.count, 5);
+    }
+
+    #[test]
+    fn trend_over_real_days() {
+        let trend = linear_trend(&[point(MONDAY, 2.0), point(MONDAY + 10, 3.0)]).unwrap();
+        assert_close(trend.slope_per_month(), 3.0);
+        assert!((trend.value_at(MONDAY as f64 + 5.0) - 2.5).abs() < 1e-2);
+        assert_eq!(linear_trend(&[point(MONDAY, 2.0)]), None);
+    }
+
+    #[test]
+    fn correlations_of_empty() {
+        assert_eq!(correlations(&[], 0), Correlations::default());
+    }
+
+    #[test]
+    fn group_means() {
+        let mut tagged = session(MONDAY + 1, 20, 20, 5, 5);
+        tagged.tags = vec!["walk".to_string()];
+        let sessions = vec![
+            session(MONDAY, 7, 10, 0, 2),
+            session(MONDAY, 8, 14, 4, 4),
+            tagged,
+        ];
+        let result = correlations(&sessions, 0);
+        assert_eq!(result.duration, vec![
+            GroupMean { label: "10–14".to_string(), mean: 3.0, count: 2 },
+            GroupMean { label: "20–24".to_string(), mean: 5.0, count: 1 },
+        ]);
+        assert_eq!(result.delay[0], GroupMean { label: "0–4".to_string(), mean: 3.0, count: 2 });
+        assert_eq!(result.time_of_day[0].label, "06");
+        assert_eq!(result.weekday[0], GroupMean { label: "Mon".to_string(), mean: 3.0, count: 2 });
+        assert_eq!(result.weekday[1].label, "Tue");
+        assert_eq!(result.tags, vec![GroupMean { label: "walk".to_string(), mean: 5.0, count: 1 }]);
+        assert!(result.duration_regression.unwrap().slope > 0.0);
+    }
+
+    #[test]
+    fn weekday_respects_day_start() {
+        // Tuesday 01:00 is still Monday if a day starts at 02:00
+        let sessions = vec![session(MONDAY + 1, 1, 15, 0, 3)];
+        assert_eq!(correlations(&sessions, 0).weekday[0].label, "Tue");
+        assert_eq!(correlations(&sessions, 2 * 60).weekday[0].label, "Mon");
+    }
+}
@@ -1,19 +1,30 @@
 #[cfg(cordova)]
 use wasm_bindgen::prelude::*;
+use serde::{Deserialize, Serialize};
 use yew::prelude::*;
 use yew_router::prelude::*;
 
+mod ambient;
 mod components;
+mod export;
+mod latency;
 mod page_components;
+mod sounds;
+mod speech;
+mod synth;
+mod vibration;
 
 use crate::db::Db;
 use page_components::home::Home;
 use page_components::about::About;
+use page_components::achievements::Achievements;
+use page_components::history::History;
 use page_components::settings::Settings;
+use page_components::stats::Stats;
 
 pub const INITIAL_DELAY: usize = 3 * 60;
 pub const INITIAL_DURATION: usize = 15 * 60;
-const MAX_DURATION: usize = 30 * 60;
+pub const MAX_DURATION: usize = 30 * 60;
 const MIN_ACTIVE_SESSION: usize = 5 * 60;
 
 #[cfg(cordova)]
@@ -41,15 +52,21 @@ pub enum Route {
     About,
     #[at("/settings")]
     Settings,
+    #[at("/history")]
+    History,
+    #[at("/stats")]
+    Stats,
+    #[at("/achievements")]
+    Achievements,
 }
 
 pub enum AppMsg {
-    OnVolumeChange(VolumeLevel),
+    OnVolumeChange(Volume),
     OnDNDChange(bool),
 }
 
 pub struct App {
-    volume: VolumeLevel,
+    volume: Volume,
     dnd: bool,
 }
 
@@ -60,6 +77,7 @@ impl Component for App {
     fn create(_ctx: &Context<Self>) -> Self {
         let db = Db::new();
         db.remove_legacy_keys();
+        db.migrate_sound_volume();
 
         Self {
             volume: db.get_sound_volume(),
@@ -85,7 +103,7 @@ impl Component for App {
 
     fn view(&self, ctx: &Context<Self>) -> Html {
         let switch = {
-            let volume = self.volume.clone();
+            let volume = self.volume;
             let on_volume_change = ctx.link().callback(|val| AppMsg::OnVolumeChange(val));
             let dnd = self.dnd;
             let on_dnd_change = ctx.link().callback(|val| AppMsg::OnDNDChange(val));
@@ -98,6 +116,15 @@ impl Component for App {
                     Route::About => html! {
                         <About />
                     },
+                    Route::History => html! {
+                        <History />
+                    },
+                    Route::Stats => html! {
+                        <Stats />
+                    },
+                    Route::Achievements => html! {
+                        <Achievements />
+                    },
                     Route::Settings => html! {
                         <Settings
                             {volume}
@@ -118,45 +145,62 @@ impl Component for App {
     }
 }
 
+/// Role of a bell sound in a session
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Sound {
-    Bowl,
-    Ding,
+    /// Session start
+    Opening,
+    /// Signal bells
+    Intermediate,
+    /// Session end
+    Final,
 }
 
-#[derive(Copy, Clone, Debug, Default, PartialEq)]
-pub enum VolumeLevel {
-    #[default]
-    Max,
-    Moderate,
-    Low,
+/// Sound volume settings in percents
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Volume {
+    pub master: u32,
+    /// Gain of a session start sound
+    pub opening: u32,
+    /// Gain of signal bells
+    pub intermediate: u32,
+    /// Gain of a session end sound
+    pub closing: u32,
 }
 
-impl VolumeLevel {
-    pub fn from_config_value(value: usize) -> Self {
-        match value {
-            2 => Self::Max,
-            1 => Self::Moderate,
-            0 => Self::Low,
-            _ => unimplemented!(),
+impl Default for Volume {
+    fn default() -> Self {
+        Self {
+            master: 100,
+            opening: 100,
+            intermediate: 100,
+            closing: 100,
         }
     }
+}
 
-    pub fn numeric_value(&self) -> f64 {
-        match self {
-            Self::Max => 1.0,
-            Self::Moderate if is_android() => 0.2,
-            Self::Moderate => 0.5,
-            Self::Low if is_android() => 0.05,
-            Self::Low => 0.2,
-        }
+impl Volume {
+    /// Converts a volume level of old versions (0 is low, 2 is max).
+    pub fn from_level(level: usize) -> Self {
+        let master = match level {
+            2 => 100,
+            1 => 50,
+            _ => 20,
+        };
+
+        Self { master, ..Self::default() }
     }
 
-    pub fn config_value(&self) -> usize {
-        match self {
-            Self::Max => 2,
-            Self::Moderate => 1,
-            Self::Low => 0,
-        }
+    /// Returns a volume (0.0 to 1.0) to play a sound with.
+    pub fn gain(&self, sound: Sound) -> f64 {
+        let gain = match sound {
+            Sound::Opening => self.opening,
+            Sound::Intermediate => self.intermediate,
+            Sound::Final => self.closing,
+        };
+        let value = self.master as f64 / 100.0 * gain as f64 / 100.0;
+        // Android media volume is much louder at low levels
+        if is_android() { value * value } else { value }
     }
 }
 
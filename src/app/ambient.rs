@@ -0,0 +1,185 @@
+#[cfg(cordova)]
+use gloo_events::EventListener;
+use gloo_timers::callback::Timeout;
+#[cfg(cordova)]
+use web_sys::AudioContextState;
+use web_sys::{AudioBufferSourceNode, AudioContext, AudioScheduledSourceNode, BiquadFilterType, GainNode};
+
+use crate::settings::AmbientSound;
+
+/// Length of a looped noise buffer in seconds
+const LOOP_SECONDS: f32 = 6.0;
+/// Time to fade in at a session start and out at its end in seconds
+const FADE_SECONDS: f64 = 5.0;
+/// Part of a volume left while a bell rings
+const DUCK_LEVEL: f64 = 0.25;
+/// Time a bell is heard over a lowered background in seconds
+const DUCK_SECONDS: f64 = 4.0;
+/// Time to lower or raise a background around a bell in seconds
+const DUCK_RAMP: f64 = 0.1;
+/// Frequency of slow loudness changes of a stream in Hz
+const STREAM_LFO: f32 = 0.13;
+
+/// Looping background noise played during a session
+pub struct Ambient {
+    context: AudioContext,
+    source: AudioBufferSourceNode,
+    output: GainNode,
+    volume: f64,
+    /// Audio context time the fade-in is over at
+    faded_in_at: f64,
+    /// Resumes a context the WebView suspends in background
+    #[cfg(cordova)]
+    _keep_alive: EventListener,
+}
+
+impl Ambient {
+    /// Starts playing a soundscape fading it in.
+    /// Returns `None` if Web Audio isn't available.
+    pub fn start(sound: AmbientSound, volume: f64) -> Option<Self> {
+        let context = AudioContext::new().ok()?;
+        let _ = context.resume();
+        let sample_rate = context.sample_rate();
+        let length = (sample_rate * LOOP_SECONDS) as u32;
+        let buffer = context.create_buffer(1, length, sample_rate).unwrap();
+        let samples = match sound {
+            AmbientSound::BrownNoise => brown_noise(length as usize),
+            AmbientSound::Rain | AmbientSound::Stream => pink_noise(length as usize),
+        };
+        buffer.copy_to_channel(&samples, 0).unwrap();
+
+        let source = context.create_buffer_source().unwrap();
+        source.set_buffer(Some(&buffer));
+        source.set_loop(true);
+        let output = context.create_gain().unwrap();
+        let now = context.current_time();
+        output.gain().set_value_at_time(0.0, now).unwrap();
+        output.gain().linear_ramp_to_value_at_time(volume as f32, now + FADE_SECONDS).unwrap();
+
+        match sound {
+            AmbientSound::BrownNoise => {
+                source.connect_with_audio_node(&output).unwrap();
+            }
+            AmbientSound::Rain => {
+                // Keep the hiss of drops and drop the rumble
+                let filter = context.create_biquad_filter().unwrap();
+                filter.set_type(BiquadFilterType::Highpass);
+                filter.frequency().set_value(500.0);
+                source.connect_with_audio_node(&filter).unwrap();
+                filter.connect_with_audio_node(&output).unwrap();
+            }
+            AmbientSound::Stream => {
+                let filter = context.create_biquad_filter().unwrap();
+                filter.set_type(BiquadFilterType::Bandpass);
+                filter.frequency().set_value(700.0);
+                filter.q().set_value(0.6);
+                // Slowly changing loudness makes noise sound like running water
+                let swell = context.create_gain().unwrap();
+                swell.gain().set_value(0.8);
+                let lfo = context.create_oscillator().unwrap();
+                lfo.frequency().set_value(STREAM_LFO);
+                let depth = context.create_gain().unwrap();
+                depth.gain().set_value(0.2);
+                lfo.connect_with_audio_node(&depth).unwrap();
+                depth.connect_with_audio_param(&swell.gain()).unwrap();
+                lfo.start().unwrap();
+                source.connect_with_audio_node(&filter).unwrap();
+                filter.connect_with_audio_node(&swell).unwrap();
+                swell.connect_with_audio_node(&output).unwrap();
+            }
+        }
+        output.connect_with_audio_node(&context.destination()).unwrap();
+        source.start().unwrap();
+
+        #[cfg(cordova)]
+        let _keep_alive = EventListener::new(&context, "statechange", {
+            let context = context.clone();
+            move |_| {
+                if context.state() == AudioContextState::Suspended {
+                    let _ = context.resume();
+                }
+            }
+        });
+
+        Some(Self {
+            context,
+            source,
+            output,
+            volume,
+            faded_in_at: now + FADE_SECONDS,
+            #[cfg(cordova)]
+            _keep_alive,
+        })
+    }
+
+    /// Lowers a background for a while so a bell is never masked.
+    /// A background that is still fading in finishes fading after that.
+    pub fn duck(&self) {
+        let now = self.context.current_time();
+        let gain = self.output.gain();
+        let current = gain.value();
+        let low = current.min((self.volume * DUCK_LEVEL) as f32);
+        gain.cancel_scheduled_values(now).unwrap();
+        gain.set_value_at_time(current, now).unwrap();
+        gain.linear_ramp_to_value_at_time(low, now + DUCK_RAMP).unwrap();
+        gain.set_value_at_time(low, now + DUCK_SECONDS).unwrap();
+        let restored_at = self.faded_in_at.max(now + DUCK_SECONDS + 1.0);
+        gain.linear_ramp_to_value_at_time(self.volume as f32, restored_at).unwrap();
+    }
+
+    /// Makes sure a background keeps playing while the app is in background.
+    #[cfg(cordova)]
+    pub fn keep_playing(&self) {
+        let _ = self.context.resume();
+    }
+
+    /// Silences a background while a session is paused and brings it back.
+    pub fn set_paused(&self, paused: bool) {
+        let now = self.context.current_time();
+        let gain = self.output.gain();
+        let level = if paused { 0.0 } else { self.volume as f32 };
+        gain.cancel_scheduled_values(now).unwrap();
+        gain.set_value_at_time(gain.value(), now).unwrap();
+        gain.linear_ramp_to_value_at_time(level, now + 1.0).unwrap();
+    }
+
+    /// Fades a background out and releases audio resources.
+    pub fn stop(self) {
+        let now = self.context.current_time();
+        let gain = self.output.gain();
+        gain.cancel_scheduled_values(now).unwrap();
+        gain.set_value_at_time(gain.value(), now).unwrap();
+        gain.linear_ramp_to_value_at_time(0.0, now + FADE_SECONDS).unwrap();
+        let source: &AudioScheduledSourceNode = &self.source;
+        source.stop_with_when(now + FADE_SECONDS).unwrap();
+        let context = self.context;
+        Timeout::new((FADE_SECONDS * 1000.0) as u32 + 100, move || {
+            let _ = context.close();
+        }).forget();
+    }
+}
+
+fn pink_noise(length: usize) -> Vec<f32> {
+    // Paul Kellet's economy filter
+    let (mut b0, mut b1, mut b2) = (0.0, 0.0, 0.0);
+    (0..length)
+        .map(|_| {
+            let white = rand::random::<f32>() * 2.0 - 1.0;
+            b0 = 0.99765 * b0 + white * 0.0990460;
+            b1 = 0.96300 * b1 + white * 0.2965164;
+            b2 = 0.57000 * b2 + white * 1.0526913;
+            (b0 + b1 + b2 + white * 0.1848) * 0.2
+        })
+        .collect()
+}
+
+fn brown_noise(length: usize) -> Vec<f32> {
+    let mut last = 0.0;
+    (0..length)
+        .map(|_| {
+            let white = rand::random::<f32>() * 2.0 - 1.0;
+            last = (last + 0.02 * white) / 1.02;
+            last * 3.5
+        })
+        .collect()
+}
@@ -1,5 +1,7 @@
 pub mod button;
 pub mod charts;
+pub mod goal_ring;
+pub mod heatmap;
 pub mod main_button;
 pub mod modal;
 pub mod rating_modal;
@@ -7,3 +9,4 @@ pub mod section_title;
 pub mod session_controls;
 pub mod sound_range;
 pub mod switch;
+pub mod visual_cue;
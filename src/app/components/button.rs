@@ -7,6 +7,8 @@ pub struct ButtonProps {
     pub icon: String,
     pub alt_icon: String,
     pub in_session: bool,
+    #[prop_or_default]
+    pub disabled: bool,
     pub on_click: Callback<()>,
 }
 
@@ -26,7 +28,7 @@ pub fn button(props: &ButtonProps) -> Html {
     };
 
     html! {
-        <button class="round-button" onclick={on_click}>
+        <button class="round-button" disabled={props.disabled} onclick={on_click}>
             <div class="icon-container">
                 <img class="icon" src={icon_url} />
             </div>
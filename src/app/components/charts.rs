@@ -1,31 +1,319 @@
 use plotters::coord::Shift;
 use plotters::prelude::*;
-use web_sys::HtmlDivElement;
+use wasm_bindgen::JsCast;
+use web_sys::{Element, HtmlDivElement, PointerEvent, WheelEvent};
 use yew::prelude::*;
 
+use crate::analytics::{ewma, linear_trend, rolling_mean};
+use crate::app::export::{export_buttons, ChartImage, ExportFormat};
+use crate::calendar::{civil_from_days, weekday, MONTHS};
+use crate::settings::ChartOverlays;
+use crate::stats::{Granularity, PeriodAvg};
+
+/// Minimal number of periods shown so sparse data isn't stretched over the chart
+const MIN_PERIODS_SHOWN: i64 = 7;
+/// Maximal number of periods shown with every value drawn as a dot
+const MAX_PERIODS_WITH_DOTS: f64 = 45.0;
+/// Maximal number of periods shown with session counts
+const MAX_PERIODS_WITH_COUNTS: f64 = 15.0;
+const LINE_COLOR: RGBColor = RGBColor(97, 97, 97);
+const BAND_COLOR: RGBAColor = RGBAColor(97, 97, 97, 0.2);
+const ROLLING_MEAN_COLOR: RGBColor = RGBColor(66, 110, 160);
+const EWMA_COLOR: RGBColor = RGBColor(150, 70, 120);
+const TREND_COLOR: RGBColor = RGBColor(0, 0, 0);
+/// Minimal number of periods shown when zoomed in
+const MIN_PERIODS_ZOOMED: i64 = 3;
+/// Zoom factor of a mouse wheel step
+const WHEEL_ZOOM: f64 = 1.2;
+/// Maximal distance from a pointer to a point with a tooltip (as a part of x range)
+const TOOLTIP_DISTANCE: f64 = 0.05;
+/// Window of a rolling mean in periods
+const ROLLING_MEAN_PERIODS: i64 = 7;
+/// Weight of a new value in EWMA
+const EWMA_ALPHA: f32 = 0.3;
+
 #[derive(Properties, PartialEq)]
 pub struct ScoreChartProps {
-    pub avgs: Vec<f32>,
+    /// Period averages in ascending order of periods
+    pub points: Vec<PeriodAvg>,
+    #[prop_or_default]
+    pub granularity: Granularity,
+    #[prop_or_default]
+    pub overlays: ChartOverlays,
+}
+
+pub enum ChartMsg {
+    PointerDown(PointerEvent),
+    PointerMove(PointerEvent),
+    PointerUp(PointerEvent),
+    PointerLeave,
+    Wheel(WheelEvent),
+    ResetView,
+    Export(ExportFormat),
+}
+
+/// Pointer gesture in progress
+enum Gesture {
+    /// Dragging with one pointer: start x (px) and x range at start
+    Pan(f64, (f64, f64)),
+    /// Pinching with two pointers: start distance (px), center (days) and x range at start
+    Pinch(f64, f64, (f64, f64)),
 }
 
+/// X range, size of a chart and a props version an SVG was drawn with
+type DrawParams = ((f64, f64), (f64, f64), u32);
+
 pub struct ScoreChart {
     wrapper_ref: NodeRef,
+    /// X range (in days) set by zooming and panning, default range if `None`
+    view_range: Option<(f64, f64)>,
+    /// Index of a point under a pointer
+    hovered: Option<usize>,
+    /// Active pointers: id and x (px)
+    pointers: Vec<(i32, f64)>,
+    gesture: Option<Gesture>,
+    /// Incremented when props change to force a redraw
+    version: u32,
+    /// Parameters of the last drawn SVG to skip redundant redraws
+    last_draw: Option<DrawParams>,
+    /// Last drawn chart to export
+    image: ChartImage,
+}
+
+impl ScoreChart {
+    fn x_range(&self, ctx: &Context<Self>) -> (f64, f64) {
+        self.view_range
+            .unwrap_or_else(|| days_range(&ctx.props().points, ctx.props().granularity))
+    }
+
+    /// Returns pointer x position relative to the chart and chart width.
+    fn pointer_x(&self, e: &PointerEvent) -> (f64, f64) {
+        let wrapper: HtmlDivElement = self.wrapper_ref.cast().unwrap();
+        let rect = wrapper.get_bounding_client_rect();
+        (e.client_x() as f64 - rect.left(), rect.width().max(1.0))
+    }
+
+    /// Finds a point closest to a pointer.
+    fn point_at(&self, ctx: &Context<Self>, x: f64, width: f64) -> Option<usize> {
+        let x_range = self.x_range(ctx);
+        let day = x_range.0 + x / width * (x_range.1 - x_range.0);
+        let max_distance = (ctx.props().granularity.days() as f64)
+            .max((x_range.1 - x_range.0) * TOOLTIP_DISTANCE);
+        ctx.props().points
+            .iter()
+            .enumerate()
+            .map(|(i, p)| (i, (p.day as f64 - day).abs()))
+            .filter(|(_, distance)| *distance <= max_distance)
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(i, _)| i)
+    }
+
+    /// Sets x range keeping it within sensible limits.
+    fn set_view_range(&mut self, ctx: &Context<Self>, range: (f64, f64)) {
+        // There is nothing to pan or zoom without data
+        if ctx.props().points.is_empty() {
+            return;
+        }
+        let default = days_range(&ctx.props().points, ctx.props().granularity);
+        let min_span = (MIN_PERIODS_ZOOMED * ctx.props().granularity.days()) as f64;
+        let max_span = (default.1 - default.0).max(min_span);
+        let span = (range.1 - range.0).clamp(min_span, max_span);
+        // A span wider than the data keeps the latest day in view
+        let start = range.0.min(default.1 - span).max(default.0.min(default.1 - span));
+        self.view_range = Some((start, start + span));
+    }
+
+    /// Zooms around a day by a factor (> 1 zooms out).
+    fn zoom(&mut self, ctx: &Context<Self>, range: (f64, f64), center: f64, factor: f64) {
+        self.set_view_range(ctx, (
+            center - (center - range.0) * factor,
+            center + (range.1 - center) * factor,
+        ));
+    }
 }
 
 impl Component for ScoreChart {
-    type Message = ();
+    type Message = ChartMsg;
     type Properties = ScoreChartProps;
 
     fn create(_ctx: &Context<Self>) -> Self {
         Self {
             wrapper_ref: NodeRef::default(),
+            view_range: None,
+            hovered: None,
+            pointers: Vec::new(),
+            gesture: None,
+            version: 0,
+            last_draw: None,
+            image: ChartImage::default(),
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            ChartMsg::PointerDown(e) => {
+                // Keep receiving events while a pointer is dragged outside of the chart
+                if let Some(area) = e.current_target().and_then(|t| t.dyn_into::<Element>().ok()) {
+                    let _ = area.set_pointer_capture(e.pointer_id());
+                }
+                let (x, width) = self.pointer_x(&e);
+                self.pointers.retain(|(id, _)| *id != e.pointer_id());
+                self.pointers.push((e.pointer_id(), x));
+                let x_range = self.x_range(ctx);
+                self.gesture = match self.pointers.as_slice() {
+                    [(_, x)] => Some(Gesture::Pan(*x, x_range)),
+                    [(_, x1), (_, x2), ..] => {
+                        let center = x_range.0 + (x1 + x2) / 2.0 / width * (x_range.1 - x_range.0);
+                        Some(Gesture::Pinch((x1 - x2).abs().max(1.0), center, x_range))
+                    }
+                    [] => None,
+                };
+                self.hovered = self.point_at(ctx, x, width);
+            }
+            ChartMsg::PointerMove(e) => {
+                let (x, width) = self.pointer_x(&e);
+                if let Some(pointer) = self.pointers.iter_mut().find(|(id, _)| *id == e.pointer_id()) {
+                    pointer.1 = x;
+                }
+                match (&self.gesture, self.pointers.as_slice()) {
+                    (Some(Gesture::Pan(start_x, range)), [_]) => {
+                        let range = *range;
+                        let shift = (start_x - x) / width * (range.1 - range.0);
+                        if shift != 0.0 {
+                            self.set_view_range(ctx, (range.0 + shift, range.1 + shift));
+                        }
+                    }
+                    (Some(Gesture::Pinch(distance, center, range)), [(_, x1), (_, x2), ..]) => {
+                        let factor = distance / (x1 - x2).abs().max(1.0);
+                        let (center, range) = (*center, *range);
+                        self.zoom(ctx, range, center, factor);
+                    }
+                    _ => {}
+                }
+                self.hovered = self.point_at(ctx, x, width);
+            }
+            ChartMsg::PointerUp(e) => {
+                self.pointers.retain(|(id, _)| *id != e.pointer_id());
+                // Continue panning with a remaining pointer
+                self.gesture = self.pointers
+                    .first()
+                    .map(|(_, x)| Gesture::Pan(*x, self.x_range(ctx)));
+            }
+            ChartMsg::PointerLeave => {
+                self.pointers.clear();
+                self.gesture = None;
+                self.hovered = None;
+            }
+            ChartMsg::Wheel(e) => {
+                e.prevent_default();
+                let wrapper: HtmlDivElement = self.wrapper_ref.cast().unwrap();
+                let rect = wrapper.get_bounding_client_rect();
+                let x_range = self.x_range(ctx);
+                let center = x_range.0
+                    + (e.client_x() as f64 - rect.left()) / rect.width().max(1.0) * (x_range.1 - x_range.0);
+                let factor = if e.delta_y() > 0.0 { WHEEL_ZOOM } else { 1.0 / WHEEL_ZOOM };
+                self.zoom(ctx, x_range, center, factor);
+            }
+            ChartMsg::ResetView => {
+                self.view_range = None;
+                self.hovered = None;
+            }
+            ChartMsg::Export(format) => {
+                self.image.save(format, "score-chart");
+                return false;
+            }
         }
+        true
+    }
+
+    fn changed(&mut self, _ctx: &Context<Self>, _old_props: &Self::Properties) -> bool {
+        self.view_range = None;
+        self.hovered = None;
+        self.version += 1;
+        true
     }
 
-    fn view(&self, _ctx: &Context<Self>) -> Html {
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let points = &ctx.props().points;
+        let granularity = ctx.props().granularity;
+        let x_range = self.x_range(ctx);
+        let labels = date_labels(x_range);
+        let x_position = |day: i64| (day as f64 - x_range.0) / (x_range.1 - x_range.0) * 100.0;
+        let is_visible = |day: i64| (x_range.0..=x_range.1).contains(&(day as f64));
+        let counts_visible =
+            (x_range.1 - x_range.0) / granularity.days() as f64 <= MAX_PERIODS_WITH_COUNTS;
+        let tooltip = self.hovered
+            .and_then(|i| points.get(i))
+            .filter(|point| is_visible(point.day))
+            .map(|point| {
+                let left = x_position(point.day).clamp(15.0, 85.0);
+                html! {
+                    <div class="chart-tooltip" style={format!("left: {left}%")}>
+                        <div>{ period_title(point.day, granularity) }</div>
+                        <div>{ format!("Average: {:.2}", point.avg) }</div>
+                        <div>{ format!("Sessions: {}", point.count) }</div>
+                    </div>
+                }
+            });
+
+        let link = ctx.link();
         html! {
-            <div ref={self.wrapper_ref.clone()} class="chart-wrapper" >
-                <svg viewBox="0 0 100 100" />
+            <div class="score-chart">
+                <div class="chart-area"
+                    onpointerdown={link.callback(ChartMsg::PointerDown)}
+                    onpointermove={link.callback(ChartMsg::PointerMove)}
+                    onpointerup={link.callback(ChartMsg::PointerUp)}
+                    onpointercancel={link.callback(ChartMsg::PointerUp)}
+                    onpointerleave={link.callback(|_| ChartMsg::PointerLeave)}
+                    onwheel={link.callback(ChartMsg::Wheel)}
+                >
+                    <div ref={self.wrapper_ref.clone()} class="chart-wrapper" >
+                        <svg viewBox="0 0 100 100" />
+                    </div>
+                    if counts_visible {
+                        <div class="chart-counts">
+                        { points
+                            .iter()
+                            .filter(|point| is_visible(point.day))
+                            .map(|point| {
+                                let style = format!(
+                                    "left: {}%; bottom: {}%",
+                                    x_position(point.day),
+                                    point.avg / 5.0 * 100.0,
+                                );
+                                html! { <span {style}>{ point.count }</span> }
+                            })
+                            .collect::<Html>()
+                        }
+                        </div>
+                    }
+                    { tooltip.unwrap_or_default() }
+                    if self.view_range.is_some() {
+                        <button class="text-button chart-reset"
+                            onpointerdown={Callback::from(|e: PointerEvent| e.stop_propagation())}
+                            onclick={link.callback(|_| ChartMsg::ResetView)}
+                        >
+                            { "Reset" }
+                        </button>
+                    }
+                </div>
+                <div class="chart-axis">
+                { labels
+                    .into_iter()
+                    .map(|(day, label)| html! {
+                        <span style={format!("left: {}%", x_position(day))}>{ label }</span>
+                    })
+                    .collect::<Html>()
+                }
+                </div>
+                <div class="chart-footer">
+                    { export_buttons(link.callback(ChartMsg::Export)) }
+                    if let Some(trend) = ctx.props().overlays.trend.then(|| linear_trend(points)).flatten() {
+                        <div class="chart-trend">
+                            { format!("Trend: {:+.1} points/month", trend.slope_per_month()) }
+                        </div>
+                    }
+                </div>
             </div>
         }
     }
@@ -34,6 +322,13 @@ impl Component for ScoreChart {
         let mut svg = String::new();
         let div_wrapper: HtmlDivElement = self.wrapper_ref.cast().unwrap();
         let rect = div_wrapper.get_bounding_client_rect();
+        let x_range = self.x_range(ctx);
+        // Tooltip changes don't need a new SVG
+        let draw = (x_range, (rect.width(), rect.height()), self.version);
+        if self.last_draw == Some(draw) {
+            return;
+        }
+        self.last_draw = Some(draw);
 
         {
             let backend = SVGBackend::with_string(
@@ -42,14 +337,33 @@ impl Component for ScoreChart {
             );
             let chart = backend.into_drawing_area();
 
+            let points = &ctx.props().points;
+            let granularity = ctx.props().granularity;
             draw_grid(&chart);
-            draw_avgs(&chart, &ctx.props().avgs);
+            draw_avgs(&chart, points, granularity, x_range);
+            draw_overlays(&chart, points, granularity, x_range, &ctx.props().overlays);
         }
 
+        let labels: Vec<(f64, String)> = date_labels(x_range)
+            .into_iter()
+            .map(|(day, label)| ((day as f64 - x_range.0) / (x_range.1 - x_range.0), label))
+            .collect();
+        self.image = ChartImage::new(&svg, rect.width() as u32, rect.height() as u32, &labels);
         div_wrapper.set_inner_html(&svg);
     }
 }
 
+/// Returns a title of a period for a tooltip.
+fn period_title(day: i64, granularity: Granularity) -> String {
+    let (y, m, d) = civil_from_days(day);
+    let month = MONTHS[m as usize - 1];
+    match granularity {
+        Granularity::Day => format!("{month} {d}, {y}"),
+        Granularity::Week => format!("Week of {month} {d}, {y}"),
+        Granularity::Month => format!("{month} {y}"),
+    }
+}
+
 fn draw_grid(chart: &DrawingArea<SVGBackend<'_>, Shift>) {
     let child_drawing_areas = chart.split_evenly((5, 1));
     for (i, area) in child_drawing_areas.into_iter().enumerate() {
@@ -65,34 +379,266 @@ fn draw_grid(chart: &DrawingArea<SVGBackend<'_>, Shift>) {
     }
 }
 
-fn draw_avgs(chart: &DrawingArea<SVGBackend<'_>, Shift>, avgs: &Vec<f32>) {
-    let color = RGBColor(97, 97, 97);
-    if avgs.len() > 1 {
-        let x_len = (avgs.len() - 1) as f64;
-        let values = || {
-            avgs
+fn draw_avgs(
+    chart: &DrawingArea<SVGBackend<'_>, Shift>,
+    points: &[PeriodAvg],
+    granularity: Granularity,
+    x_range: (f64, f64),
+) {
+    if points.is_empty() {
+        return;
+    }
+    let mut chart = ChartBuilder::on(chart)
+        .build_cartesian_2d(x_range.0..x_range.1, 0.0..5.0)
+        .unwrap();
+    // Split the line where periods were missed
+    let mut segments: Vec<Vec<&PeriodAvg>> = Vec::new();
+    let mut previous_day: Option<i64> = None;
+    for point in points {
+        match (segments.last_mut(), previous_day) {
+            (Some(segment), Some(day)) if granularity.next_period_start(day) == point.day => {
+                segment.push(point);
+            }
+            _ => segments.push(vec![point]),
+        }
+        previous_day = Some(point.day);
+    }
+    // Shaded band between minimal and maximal session scores
+    for segment in &segments {
+        if segment.len() > 1 {
+            let band: Vec<(f64, f64)> = segment
                 .iter()
-                .enumerate()
-                .map(|(x, y)| (x as f64, *y as f64))
-        };
-        ChartBuilder::on(&chart)
-            .build_cartesian_2d(0.0..x_len, 0.0..5.0)
-            .unwrap()
-            .draw_series(
-                LineSeries::new(
-                    values(),
-                    color.stroke_width(3),
-                )
-            )
+                .map(|p| (p.day as f64, p.max as f64))
+                .chain(segment.iter().rev().map(|p| (p.day as f64, p.min as f64)))
+                .collect();
+            chart.draw_series(std::iter::once(Polygon::new(band, BAND_COLOR.filled()))).unwrap();
+        } else {
+            let p = segment[0];
+            chart
+                .draw_series(std::iter::once(PathElement::new(
+                    [(p.day as f64, p.min as f64), (p.day as f64, p.max as f64)],
+                    BAND_COLOR.stroke_width(6),
+                )))
+                .unwrap();
+        }
+    }
+    let coords = |segment: &Vec<&PeriodAvg>| {
+        segment
+            .iter()
+            .map(|p| (p.day as f64, p.avg as f64))
+            .collect::<Vec<_>>()
+    };
+    for segment in segments.iter().filter(|s| s.len() > 1) {
+        chart
+            .draw_series(LineSeries::new(coords(segment), LINE_COLOR.stroke_width(3)))
             .unwrap();
-    } else if avgs.len() == 1 {
-        // Draw a single value as circle
-        let dot = EmptyElement::at((0.0, avgs[0] as f64))
-            + Circle::new((0, 0), 4, ShapeStyle::from(color).filled());
-        let _ = ChartBuilder::on(chart)
-            .build_cartesian_2d(0.0..0.0, 0.0..5.0)
-            .unwrap()
-            .plotting_area()
-            .draw(&dot);
     }
+    // Draw dots if there is enough room for them or a value has no neighbours
+    let dots_visible = (x_range.1 - x_range.0) / granularity.days() as f64 <= MAX_PERIODS_WITH_DOTS;
+    chart
+        .draw_series(
+            segments
+                .iter()
+                .filter(|s| dots_visible || s.len() == 1)
+                .flat_map(coords)
+                .map(|point| Circle::new(point, 4, ShapeStyle::from(LINE_COLOR).filled()))
+        )
+        .unwrap();
+}
+
+fn draw_overlays(
+    chart: &DrawingArea<SVGBackend<'_>, Shift>,
+    points: &[PeriodAvg],
+    granularity: Granularity,
+    x_range: (f64, f64),
+    overlays: &ChartOverlays,
+) {
+    if points.len() < 2 {
+        return;
+    }
+    let mut chart = ChartBuilder::on(chart)
+        .build_cartesian_2d(x_range.0..x_range.1, 0.0..5.0)
+        .unwrap();
+    let as_coords = |values: Vec<(i64, f32)>| {
+        values
+            .into_iter()
+            .map(|(day, value)| (day as f64, value as f64))
+            .collect::<Vec<_>>()
+    };
+    if overlays.rolling_mean {
+        chart
+            .draw_series(LineSeries::new(
+                as_coords(rolling_mean(points, ROLLING_MEAN_PERIODS * granularity.days())),
+                ROLLING_MEAN_COLOR.stroke_width(2),
+            ))
+            .unwrap();
+    }
+    if overlays.ewma {
+        chart
+            .draw_series(LineSeries::new(
+                as_coords(ewma(points, EWMA_ALPHA, granularity.days())),
+                EWMA_COLOR.stroke_width(2),
+            ))
+            .unwrap();
+    }
+    if let Some(trend) = overlays.trend.then(|| linear_trend(points)).flatten() {
+        let first = points.first().unwrap().day as f64;
+        let last = points.last().unwrap().day as f64;
+        chart
+            .draw_series(LineSeries::new(
+                [(first, trend.value_at(first)), (last, trend.value_at(last))],
+                TREND_COLOR.stroke_width(1),
+            ))
+            .unwrap();
+    }
+}
+
+/// Returns x axis range in days (with a half-period margin on both sides).
+fn days_range(points: &[PeriodAvg], granularity: Granularity) -> (f64, f64) {
+    let period = granularity.days();
+    let margin = period as f64 / 2.0;
+    match (points.first(), points.last()) {
+        (Some(first), Some(last)) => {
+            let span = (last.day - first.day).max((MIN_PERIODS_SHOWN - 1) * period);
+            ((last.day - span) as f64 - margin, last.day as f64 + margin)
+        }
+        _ => (0.0, 1.0),
+    }
+}
+
+/// Returns labels for an x axis adapted to a range of days.
+fn date_labels(x_range: (f64, f64)) -> Vec<(i64, String)> {
+    let first = x_range.0.ceil() as i64;
+    let last = x_range.1.floor() as i64;
+    let span = last - first;
+    (first..=last)
+        .filter_map(|day| {
+            let (y, m, d) = civil_from_days(day);
+            let month = MONTHS[m as usize - 1];
+            let label = if span < 10 {
+                Some(format!("{month} {d}"))
+                    .filter(|_| (day - first) % 2 == 0)
+            } else if span < 70 {
+                // Mondays
+                Some(format!("{month} {d}"))
+                    .filter(|_| weekday(day) == 0 && (span < 35 || (day - first) / 7 % 2 == 0))
+            } else if span < 800 {
+                let step = if span < 400 { 1 } else { 3 };
+                if d == 1 && (m - 1) % step == 0 {
+                    Some(if m == 1 { y.to_string() } else { month.to_string() })
+                } else {
+                    None
+                }
+            } else if d == 1 && m == 1 {
+                Some(y.to_string())
+            } else {
+                None
+            };
+            label.map(|label| (day, label))
+        })
+        .collect()
+}
+
+#[derive(Properties, PartialEq)]
+pub struct BarChartProps {
+    pub values: Vec<f32>,
+    pub labels: Vec<String>,
+    /// Top of a value axis (the largest value if not set)
+    #[prop_or_default]
+    pub max: Option<f32>,
+    /// File name of an exported image
+    #[prop_or(String::from("chart"))]
+    pub name: String,
+}
+
+/// Simple bar chart with labels under the bars
+pub struct BarChart {
+    wrapper_ref: NodeRef,
+    /// Last drawn chart to export
+    image: ChartImage,
+}
+
+impl Component for BarChart {
+    type Message = ExportFormat;
+    type Properties = BarChartProps;
+
+    fn create(_ctx: &Context<Self>) -> Self {
+        Self {
+            wrapper_ref: NodeRef::default(),
+            image: ChartImage::default(),
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, format: Self::Message) -> bool {
+        self.image.save(format, &ctx.props().name);
+        false
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        html! {
+            <div class="bar-chart">
+                <div ref={self.wrapper_ref.clone()} class="chart-wrapper" >
+                    <svg viewBox="0 0 100 100" />
+                </div>
+                <div class="chart-labels">
+                { ctx.props().labels
+                    .iter()
+                    .map(|label| html! { <span>{ label }</span> })
+                    .collect::<Html>()
+                }
+                </div>
+                { export_buttons(ctx.link().callback(|format| format)) }
+            </div>
+        }
+    }
+
+    fn rendered(&mut self, ctx: &Context<Self>, _first_render: bool) {
+        let mut svg = String::new();
+        let div_wrapper: HtmlDivElement = self.wrapper_ref.cast().unwrap();
+        let rect = div_wrapper.get_bounding_client_rect();
+
+        {
+            let backend = SVGBackend::with_string(
+                &mut svg,
+                (rect.width() as u32, rect.height() as u32)
+            );
+            let chart = backend.into_drawing_area();
+
+            draw_bars(&chart, &ctx.props().values, ctx.props().max);
+        }
+
+        let count = ctx.props().labels.len() as f64;
+        let labels: Vec<(f64, String)> = ctx.props().labels
+            .iter()
+            .enumerate()
+            .map(|(i, label)| ((i as f64 + 0.5) / count, label.clone()))
+            .collect();
+        self.image = ChartImage::new(&svg, rect.width() as u32, rect.height() as u32, &labels);
+
+        div_wrapper.set_inner_html(&svg);
+    }
+}
+
+fn draw_bars(chart: &DrawingArea<SVGBackend<'_>, Shift>, values: &[f32], max: Option<f32>) {
+    let color = RGBColor(223, 171, 74);
+    let max = max.unwrap_or_else(|| values.iter().cloned().fold(0.0, f32::max)) as f64;
+    if values.is_empty() || max <= 0.0 {
+        return;
+    }
+    let mut chart = ChartBuilder::on(chart)
+        .build_cartesian_2d(0.0..values.len() as f64, 0.0..max)
+        .unwrap();
+    chart
+        .draw_series(
+            values
+                .iter()
+                .enumerate()
+                .map(|(x, y)| {
+                    Rectangle::new(
+                        [(x as f64 + 0.1, 0.0), (x as f64 + 0.9, *y as f64)],
+                        color.filled(),
+                    )
+                })
+        )
+        .unwrap();
 }
@@ -0,0 +1,34 @@
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct GoalRingProps {
+    pub title: String,
+    /// Progress value in goal units
+    pub value: String,
+    /// Part of a target done, from 0 to 1
+    pub fraction: f32,
+    pub reached: bool,
+}
+
+#[function_component(GoalRing)]
+pub fn goal_ring(props: &GoalRingProps) -> Html {
+    // Circles are measured in percents with `pathLength`
+    let done = (props.fraction * 100.0).clamp(0.0, 100.0);
+    let class = if props.reached { "goal-ring reached" } else { "goal-ring" };
+
+    html! {
+        <div {class}>
+            <svg viewBox="0 0 40 40">
+                <circle class="goal-ring-track" cx="20" cy="20" r="16" pathLength="100" />
+                <circle class="goal-ring-progress"
+                    cx="20" cy="20" r="16"
+                    pathLength="100"
+                    stroke-dasharray={format!("{done} 100")}
+                    transform="rotate(-90 20 20)"
+                />
+                <text x="20" y="24" text-anchor="middle">{ props.value.clone() }</text>
+            </svg>
+            <div class="goal-title">{ props.title.clone() }</div>
+        </div>
+    }
+}
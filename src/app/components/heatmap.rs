@@ -0,0 +1,149 @@
+use plotters::prelude::*;
+use web_sys::HtmlDivElement;
+use yew::prelude::*;
+
+use crate::app::export::{export_buttons, ChartImage, ExportFormat};
+use crate::calendar::weekday;
+use crate::stats::PeriodAvg;
+
+/// Size of a cell (including a gap) in pixels
+const CELL_SIZE: f64 = 16.0;
+/// Gap between cells in pixels
+const CELL_GAP: i32 = 3;
+/// Maximal number of weeks shown
+const MAX_WEEKS: i64 = 53;
+const EMPTY_COLOR: RGBColor = RGBColor(235, 235, 235);
+
+/// Value a cell colour represents
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum HeatmapMetric {
+    #[default]
+    Score,
+    Count,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct HeatmapProps {
+    /// Daily averages in ascending order of days
+    pub days: Vec<PeriodAvg>,
+    /// Current day number
+    pub today: i64,
+    #[prop_or_default]
+    pub metric: HeatmapMetric,
+    /// Called with a day number of a tapped cell
+    pub on_select: Callback<i64>,
+}
+
+pub enum HeatmapMsg {
+    /// Cells layout has changed
+    Relayout,
+    Export(ExportFormat),
+}
+
+/// Calendar heatmap: weeks are columns, days of week are rows
+pub struct Heatmap {
+    wrapper_ref: NodeRef,
+    /// Day number of the top left cell
+    first_day: i64,
+    /// Last drawn chart to export
+    image: ChartImage,
+}
+
+impl Component for Heatmap {
+    type Message = HeatmapMsg;
+    type Properties = HeatmapProps;
+
+    fn create(_ctx: &Context<Self>) -> Self {
+        Self {
+            wrapper_ref: NodeRef::default(),
+            first_day: 0,
+            image: ChartImage::default(),
+        }
+    }
+
+    fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            HeatmapMsg::Relayout => true,
+            HeatmapMsg::Export(format) => {
+                self.image.save(format, "practice-calendar");
+                false
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let onclick = {
+            let on_select = ctx.props().on_select.clone();
+            let first_day = self.first_day;
+            let today = ctx.props().today;
+            let wrapper_ref = self.wrapper_ref.clone();
+            Callback::from(move |e: MouseEvent| {
+                let rect = wrapper_ref.cast::<HtmlDivElement>().unwrap().get_bounding_client_rect();
+                let column = ((e.client_x() as f64 - rect.left()) / CELL_SIZE) as i64;
+                let row = ((e.client_y() as f64 - rect.top()) / CELL_SIZE) as i64;
+                let day = first_day + column * 7 + row;
+                if row < 7 && day <= today {
+                    on_select.emit(day);
+                }
+            })
+        };
+
+        html! {
+            <>
+                <div ref={self.wrapper_ref.clone()} class="heatmap-wrapper" {onclick}>
+                    <svg viewBox="0 0 100 100" />
+                </div>
+                { export_buttons(ctx.link().callback(HeatmapMsg::Export)) }
+            </>
+        }
+    }
+
+    fn rendered(&mut self, ctx: &Context<Self>, _first_render: bool) {
+        let mut svg = String::new();
+        let div_wrapper: HtmlDivElement = self.wrapper_ref.cast().unwrap();
+        let rect = div_wrapper.get_bounding_client_rect();
+        let weeks = ((rect.width() / CELL_SIZE) as i64).clamp(1, MAX_WEEKS);
+        let today = ctx.props().today;
+        let first_day = today - weekday(today) as i64 - (weeks - 1) * 7;
+        if first_day != self.first_day {
+            // Let click handler know the new layout
+            self.first_day = first_day;
+            ctx.link().send_message(HeatmapMsg::Relayout);
+        }
+
+        {
+            let backend = SVGBackend::with_string(
+                &mut svg,
+                (rect.width() as u32, rect.height() as u32)
+            );
+            let area = backend.into_drawing_area();
+            let days = &ctx.props().days;
+            let max_count = days.iter().map(|d| d.count).max().unwrap_or(1) as f64;
+            let cells = (first_day..=today).map(|day| {
+                let column = ((day - first_day) / 7) as i32;
+                let row = ((day - first_day) % 7) as i32;
+                let x = column * CELL_SIZE as i32;
+                let y = row * CELL_SIZE as i32;
+                let size = CELL_SIZE as i32 - CELL_GAP;
+                let style = match days.binary_search_by_key(&day, |d| d.day) {
+                    Ok(index) => {
+                        let alpha = match ctx.props().metric {
+                            HeatmapMetric::Score => days[index].avg as f64 / 5.0,
+                            HeatmapMetric::Count => days[index].count as f64 / max_count,
+                        };
+                        // Keep cells with data distinguishable from empty ones
+                        RGBAColor(186, 138, 48, 0.15 + alpha * 0.85).filled()
+                    }
+                    Err(_) => EMPTY_COLOR.filled(),
+                };
+                Rectangle::new([(x, y), (x + size, y + size)], style)
+            });
+            for cell in cells {
+                area.draw(&cell).unwrap();
+            }
+        }
+
+        self.image = ChartImage::new(&svg, rect.width() as u32, rect.height() as u32, &[]);
+        div_wrapper.set_inner_html(&svg);
+    }
+}
@@ -1,18 +1,29 @@
-use web_sys::{HtmlInputElement, window};
+use web_sys::{HtmlInputElement, HtmlTextAreaElement, window};
 use wasm_bindgen::prelude::*;
 use yew::prelude::*;
 
 use crate::app::platform_url;
 
+/// Session rating with optional context
+pub struct Rating {
+    pub score: usize,
+    pub note: String,
+    pub tags: Vec<String>,
+}
+
 #[derive(Properties, PartialEq)]
 pub struct RatingModalProps {
     pub visible: bool,
-    pub callback: Callback<usize>,
+    /// Tags a user can pick from
+    pub tags: Vec<String>,
+    pub callback: Callback<Rating>,
 }
 
 #[function_component(RatingModal)]
 pub fn rating_modal(props: &RatingModalProps) -> Html {
     let choice = use_state(|| 0);
+    let selected_tags = use_state(Vec::<String>::new);
+    let note_input = use_node_ref();
 
     // Reset previously selected radio input
     fn reset_choice(choice: UseStateHandle<usize>) {
@@ -49,13 +60,47 @@ pub fn rating_modal(props: &RatingModalProps) -> Html {
     let on_ok = {
         let callback = props.callback.clone();
         let choice = choice.clone();
+        let selected_tags = selected_tags.clone();
+        let note_input = note_input.clone();
         Callback::from(move |_| {
-            callback.emit(*choice);
+            let mut note = String::new();
+            if let Some(input) = note_input.cast::<HtmlTextAreaElement>() {
+                note = input.value().trim().to_string();
+                input.set_value("");
+            }
+            callback.emit(Rating {
+                score: *choice,
+                note,
+                tags: (*selected_tags).clone(),
+            });
             // Reset choice for next use
             reset_choice(choice.clone());
+            selected_tags.set(Vec::new());
         })
     };
 
+    let tag_view = |tag: &String| {
+        let is_selected = selected_tags.contains(tag);
+        let onclick = {
+            let selected_tags = selected_tags.clone();
+            let tag = tag.clone();
+            Callback::from(move |_| {
+                let mut tags = (*selected_tags).clone();
+                if let Some(index) = tags.iter().position(|t| *t == tag) {
+                    tags.remove(index);
+                } else {
+                    tags.push(tag.clone());
+                }
+                selected_tags.set(tags);
+            })
+        };
+        let class = if is_selected { "tag selected" } else { "tag" };
+
+        html! {
+            <button {class} {onclick}>{ tag }</button>
+        }
+    };
+
     let style = if props.visible { "" } else { "display: none;" };
 
     let star_icon_url = platform_url("assets/icons/star.svg");
@@ -89,6 +134,12 @@ pub fn rating_modal(props: &RatingModalProps) -> Html {
                     .collect::<Html>()
                 }
                 </div>
+                if !props.tags.is_empty() {
+                    <div class="tags">
+                        { props.tags.iter().map(tag_view).collect::<Html>() }
+                    </div>
+                }
+                <textarea class="note" ref={note_input} placeholder="Note (optional)" />
                 <div class="modal-buttons">
                     <button onclick={on_reset}>{ "Reset" }</button>
                     <button onclick={on_ok}>{ "Ok" }</button>
@@ -31,8 +31,8 @@ pub fn sound_range(props: &SoundRangeProps) -> Html {
             <div class="range-slider">
                 <span class="available-range" />
                 <input type="range"
-                    min="1"
-                    max="3"
+                    min="0"
+                    max="100"
                     ref={volume_input}
                     {oninput}
                     value={props.value.to_string()}
@@ -0,0 +1,35 @@
+use yew::prelude::*;
+
+use crate::app::Sound;
+use crate::settings::{VisualSignal, VisualStyle};
+
+#[derive(Properties, PartialEq)]
+pub struct VisualCueProps {
+    pub sound: Sound,
+    /// Number of cues shown so far; a new number restarts the animation
+    pub count: usize,
+    pub settings: VisualSignal,
+}
+
+/// Full-screen colour pulse or flash shown instead of (or with) a bell
+#[function_component(VisualCue)]
+pub fn visual_cue(props: &VisualCueProps) -> Html {
+    let mut class = classes!("visual-cue");
+    class.push(match props.sound {
+        Sound::Opening => "opening",
+        Sound::Intermediate => "intermediate",
+        Sound::Final => "final",
+    });
+    class.push(match props.settings.style {
+        VisualStyle::Pulse => "pulse",
+        VisualStyle::Flash => "flash",
+    });
+    if props.settings.reduced_motion {
+        class.push("respect-motion");
+    }
+    let style = format!("--cue-intensity: {};", props.settings.intensity as f32 / 100.0);
+
+    html! {
+        <div key={props.count} {class} {style} />
+    }
+}
@@ -0,0 +1,132 @@
+use gloo_events::EventListener;
+use gloo_timers::callback::Timeout;
+use js_sys::Array;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{
+    Blob, BlobPropertyBag, CanvasRenderingContext2d, HtmlAnchorElement,
+    HtmlCanvasElement, HtmlImageElement, Url,
+};
+use yew::prelude::*;
+
+/// Height of a row of labels under a chart in pixels
+const LABELS_HEIGHT: u32 = 20;
+/// PNG pixels per SVG pixel
+const PNG_SCALE: f64 = 2.0;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum ExportFormat {
+    Svg,
+    Png,
+}
+
+/// Buttons to save a chart as an image.
+/// The Cordova WebView ignores download links, so there are none there.
+pub fn export_buttons(on_export: Callback<ExportFormat>) -> Html {
+    if cfg!(cordova) {
+        return html! {};
+    }
+    let on_svg = on_export.reform(|_: MouseEvent| ExportFormat::Svg);
+    let on_png = on_export.reform(|_: MouseEvent| ExportFormat::Png);
+
+    html! {
+        <div class="export-buttons">
+            <button class="text-button" onclick={on_svg}>{ "SVG" }</button>
+            <button class="text-button" onclick={on_png}>{ "PNG" }</button>
+        </div>
+    }
+}
+
+/// Chart image ready to be saved
+#[derive(Default)]
+pub struct ChartImage {
+    svg: String,
+    width: u32,
+    height: u32,
+}
+
+impl ChartImage {
+    /// Makes a standalone SVG from a chart drawn by plotters adding a white
+    /// background and labels under the chart. Label positions are fractions of a width.
+    pub fn new(chart_svg: &str, width: u32, height: u32, labels: &[(f64, String)]) -> Self {
+        let total_height = if labels.is_empty() { height } else { height + LABELS_HEIGHT };
+        let mut svg = format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{total_height}" viewBox="0 0 {width} {total_height}">"#
+        );
+        svg.push_str(&format!(r#"<rect width="{width}" height="{total_height}" fill="white"/>"#));
+        svg.push_str(chart_svg);
+        for (x, label) in labels {
+            svg.push_str(&format!(
+                r#"<text x="{}" y="{}" font-family="Roboto, sans-serif" font-size="12" text-anchor="middle">{}</text>"#,
+                x * width as f64,
+                height + LABELS_HEIGHT - 5,
+                escape(label),
+            ));
+        }
+        svg.push_str("</svg>");
+
+        Self {
+            svg,
+            width,
+            height: total_height,
+        }
+    }
+
+    pub fn save(&self, format: ExportFormat, name: &str) {
+        match format {
+            ExportFormat::Svg => self.save_svg(name),
+            ExportFormat::Png => self.save_png(name),
+        }
+    }
+
+    fn save_svg(&self, name: &str) {
+        let parts = Array::of1(&JsValue::from_str(&self.svg));
+        let options = BlobPropertyBag::new();
+        options.set_type("image/svg+xml");
+        let blob = Blob::new_with_str_sequence_and_options(&parts, &options).unwrap();
+        let url = Url::create_object_url_with_blob(&blob).unwrap();
+        download(&url, &format!("{name}.svg"));
+        // Give a browser time to start the download
+        Timeout::new(1_000, move || Url::revoke_object_url(&url).unwrap()).forget();
+    }
+
+    /// Rasterises the SVG through a canvas.
+    fn save_png(&self, name: &str) {
+        let document = web_sys::window().unwrap()
+            .document().unwrap();
+        let image = HtmlImageElement::new().unwrap();
+        let canvas: HtmlCanvasElement = document.create_element("canvas").unwrap()
+            .dyn_into().unwrap();
+        canvas.set_width((self.width as f64 * PNG_SCALE) as u32);
+        canvas.set_height((self.height as f64 * PNG_SCALE) as u32);
+        let file_name = format!("{name}.png");
+        let on_load = {
+            let image = image.clone();
+            move |_: &Event| {
+                let context: CanvasRenderingContext2d = canvas.get_context("2d").unwrap().unwrap()
+                    .dyn_into().unwrap();
+                context.scale(PNG_SCALE, PNG_SCALE).unwrap();
+                context.draw_image_with_html_image_element(&image, 0.0, 0.0).unwrap();
+                let url = canvas.to_data_url_with_type("image/png").unwrap();
+                download(&url, &file_name);
+            }
+        };
+        EventListener::once(&image, "load", on_load).forget();
+        let encoded: String = js_sys::encode_uri_component(&self.svg).into();
+        image.set_src(&format!("data:image/svg+xml;charset=utf-8,{encoded}"));
+    }
+}
+
+/// Makes a browser save a file from a URL.
+fn download(url: &str, file_name: &str) {
+    let document = web_sys::window().unwrap()
+        .document().unwrap();
+    let anchor: HtmlAnchorElement = document.create_element("a").unwrap()
+        .dyn_into().unwrap();
+    anchor.set_href(url);
+    anchor.set_download(file_name);
+    anchor.click();
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
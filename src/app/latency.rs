@@ -0,0 +1,146 @@
+use std::cell::Cell;
+use std::rc::Rc;
+#[cfg(cordova)]
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use gloo_events::EventListener;
+use gloo_timers::callback::Timeout;
+use js_sys::{Date, Reflect};
+#[cfg(cordova)]
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsValue;
+#[cfg(cordova)]
+use wasm_bindgen::JsCast;
+use web_sys::{AudioContext, HtmlAudioElement};
+#[cfg(cordova)]
+use web_sys::CustomEvent;
+use yew::Callback;
+
+use crate::app::is_android;
+#[cfg(cordova)]
+use crate::app::platform_url;
+use crate::app::sounds::BellSound;
+
+/// Number of test plays a latency is a median of
+const CALIBRATION_RUNS: usize = 5;
+/// Pause between test plays in ms
+pub const RUN_SPACING: u32 = 1_500;
+/// Time to wait for a test play to start in ms
+const PROBE_TIMEOUT: u32 = 5_000;
+
+#[cfg(cordova)]
+#[wasm_bindgen(raw_module = "/android_asset/www/js/aux.js")]
+extern "C" {
+    #[wasm_bindgen(js_name = measureLatency)]
+    fn measure_latency(id: u32, file: &str, rate: f64, volume: f64);
+}
+
+/// Id of the next Cordova test play to tell its result from late ones
+#[cfg(cordova)]
+static NEXT_PROBE_ID: AtomicU32 = AtomicU32::new(0);
+
+/// Output latency of a Web Audio context in ms as reported by a browser.
+/// Browsers not reporting it are assumed to have none.
+pub fn web_audio_latency(context: &AudioContext) -> f64 {
+    let get = |key: &str| {
+        Reflect::get(context, &JsValue::from_str(key))
+            .ok()
+            .and_then(|value| value.as_f64())
+            .unwrap_or(0.0)
+    };
+
+    (get("baseLatency") + get("outputLatency")) * 1000.0
+}
+
+/// Test play of a bell measuring time from a play request until
+/// the sound starts. Dropping it discards the result.
+pub struct Probe {
+    _listeners: Vec<EventListener>,
+    _audio: Option<HtmlAudioElement>,
+    _timeout: Timeout,
+}
+
+/// Plays a sound once and calls back with a latency in ms or `None`
+/// if the sound didn't start.
+pub fn probe(sound: &BellSound, volume: f64, callback: Callback<Option<f64>>) -> Probe {
+    let mut listeners = Vec::new();
+    #[cfg(cordova)]
+    {
+        let callback = callback.clone();
+        let id = NEXT_PROBE_ID.fetch_add(1, Ordering::Relaxed);
+        let document = web_sys::window().unwrap().document().unwrap();
+        listeners.push(EventListener::new(&document, "latency", move |e| {
+            let detail = e.dyn_ref::<CustomEvent>().unwrap().detail();
+            let get = |key: &str| {
+                Reflect::get(&detail, &JsValue::from_str(key)).ok().and_then(|value| value.as_f64())
+            };
+            // A test play that timed out may still report when the next one runs
+            if get("id") == Some(id as f64) {
+                callback.emit(get("latency"));
+            }
+        }));
+        measure_latency(id, &platform_url(&sound.url()), sound.rate, volume);
+    }
+    // Cordova measures its own playback (on iOS as well)
+    let audio = if !cfg!(cordova) && !is_android() {
+        let audio = HtmlAudioElement::new_with_src(&sound.url()).unwrap();
+        sound.apply_rate(&audio);
+        audio.set_volume(volume);
+        // Playback of an element reaches speakers through the same output
+        // a Web Audio context reports latency of
+        let output = AudioContext::new().map_or(0.0, |context| {
+            let latency = web_audio_latency(&context);
+            let _ = context.close();
+            latency
+        });
+        let start = Rc::new(Cell::new(0.0));
+        // Wait for the file to load so the network isn't measured
+        listeners.push(EventListener::once(&audio, "canplaythrough", {
+            let audio = audio.clone();
+            let start = start.clone();
+            move |_| {
+                start.set(Date::now());
+                let _ = audio.play();
+            }
+        }));
+        listeners.push(EventListener::once(&audio, "playing", {
+            let callback = callback.clone();
+            move |_| callback.emit(Some(Date::now() - start.get() + output))
+        }));
+        audio.load();
+        Some(audio)
+    } else {
+        None
+    };
+
+    Probe {
+        _listeners: listeners,
+        _audio: audio,
+        _timeout: Timeout::new(PROBE_TIMEOUT, move || callback.emit(None)),
+    }
+}
+
+/// Calibration in progress
+#[derive(Default)]
+pub struct Calibration {
+    samples: Vec<f64>,
+    /// Test play being measured
+    pub probe: Option<Probe>,
+    /// Timer of the next test play
+    pub next: Option<Timeout>,
+}
+
+impl Calibration {
+    /// Adds a measured latency and returns a median of all
+    /// measurements once there are enough of them.
+    pub fn add(&mut self, sample: f64) -> Option<u32> {
+        self.probe = None;
+        self.samples.push(sample);
+        if self.samples.len() < CALIBRATION_RUNS {
+            return None;
+        }
+        self.samples.sort_by(|a, b| a.total_cmp(b));
+
+        Some(self.samples[self.samples.len() / 2].round() as u32)
+    }
+}
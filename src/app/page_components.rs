@@ -1,3 +1,6 @@
 pub mod about;
+pub mod achievements;
+pub mod history;
 pub mod home;
 pub mod settings;
+pub mod stats;
@@ -0,0 +1,81 @@
+use std::collections::BTreeMap;
+
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+use crate::achievements::ACHIEVEMENTS;
+use crate::app::{Route, platform_url};
+use crate::db::Db;
+
+pub enum AchievementsMsg {
+    OnBackButton,
+}
+
+pub struct Achievements {
+    /// Unlock dates by achievement ids
+    unlocked: BTreeMap<String, String>,
+}
+
+impl Component for Achievements {
+    type Message = AchievementsMsg;
+    type Properties = ();
+
+    fn create(_ctx: &Context<Self>) -> Self {
+        let db = Db::new();
+        // Sessions may have been saved before achievements were introduced
+        db.update_achievements();
+
+        Self {
+            unlocked: db.get_achievements(),
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            AchievementsMsg::OnBackButton => {
+                let navigator = ctx.link().navigator().unwrap();
+                navigator.push(&Route::Home);
+            }
+        }
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let back_icon_url = platform_url("assets/icons/back.svg");
+
+        html! {
+            <>
+                <header>
+                    <button onclick={ctx.link().callback(|_| AchievementsMsg::OnBackButton)}>
+                        <img src={back_icon_url} />
+                    </button>
+                    <h1>{ "Achievements" }</h1>
+                </header>
+                <main class="achievements">
+                    <div class="hint">
+                        { format!("{} of {} unlocked", self.unlocked.len(), ACHIEVEMENTS.len()) }
+                    </div>
+                    { ACHIEVEMENTS
+                        .iter()
+                        .map(|achievement| {
+                            let date = self.unlocked.get(achievement.id);
+                            let class = if date.is_some() { "achievement unlocked" } else { "achievement" };
+                            html! {
+                                <section {class}>
+                                    <div>
+                                        <strong>{ achievement.title }</strong>
+                                        <div class="hint">{ achievement.description }</div>
+                                    </div>
+                                    <div class="achievement-date">
+                                        { date.cloned().unwrap_or_else(|| "Locked".to_string()) }
+                                    </div>
+                                </section>
+                            }
+                        })
+                        .collect::<Html>()
+                    }
+                </main>
+            </>
+        }
+    }
+}
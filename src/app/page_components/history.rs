@@ -0,0 +1,296 @@
+use web_sys::HtmlTextAreaElement;
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+use crate::app::{Route, platform_url};
+use crate::db::{Db, SavedSession};
+
+/// Last change that can be reverted
+enum Undo {
+    Edited(SavedSession),
+    Deleted(SavedSession),
+}
+
+#[allow(clippy::enum_variant_names)]
+pub enum HistoryMsg {
+    OnBackButton,
+    OnEdit(String),
+    OnScoreChange(usize),
+    OnNoteInput,
+    OnTagToggle(String),
+    OnSave,
+    OnCancel,
+    OnDelete(String),
+    OnUndo,
+}
+
+pub struct History {
+    /// App database
+    db: Db,
+    /// Saved sessions, most recent first
+    sessions: Vec<SavedSession>,
+    /// Timestamp of a session being edited
+    editing: Option<String>,
+    /// Score of a session being edited
+    edit_score: usize,
+    /// Note of a session being edited
+    edit_note: String,
+    /// Tags of a session being edited
+    edit_tags: Vec<String>,
+    /// User defined session tags
+    tags: Vec<String>,
+    /// Note input ref
+    note_input: NodeRef,
+    /// Last change that can be reverted
+    undo: Option<Undo>,
+}
+
+impl History {
+    fn load_sessions(&mut self) {
+        self.sessions = self.db.get_sessions();
+        self.sessions.reverse();
+    }
+}
+
+impl Component for History {
+    type Message = HistoryMsg;
+    type Properties = ();
+
+    fn create(_ctx: &Context<Self>) -> Self {
+        let db = Db::new();
+        let tags = db.get_tags();
+        let mut history = Self {
+            db,
+            sessions: Vec::new(),
+            editing: None,
+            edit_score: 0,
+            edit_note: String::new(),
+            edit_tags: Vec::new(),
+            tags,
+            note_input: NodeRef::default(),
+            undo: None,
+        };
+        history.load_sessions();
+
+        history
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            HistoryMsg::OnBackButton => {
+                let navigator = ctx.link().navigator().unwrap();
+                navigator.push(&Route::Home);
+            }
+            HistoryMsg::OnEdit(ts) => {
+                if let Some(session) = self.sessions.iter().find(|s| s.ts == ts) {
+                    self.edit_score = session.score;
+                    self.edit_note = session.note.clone();
+                    self.edit_tags = session.tags.clone();
+                    self.editing = Some(ts);
+                }
+            }
+            HistoryMsg::OnScoreChange(score) => {
+                self.edit_score = score;
+            }
+            HistoryMsg::OnNoteInput => {
+                if let Some(input) = self.note_input.cast::<HtmlTextAreaElement>() {
+                    self.edit_note = input.value();
+                }
+            }
+            HistoryMsg::OnTagToggle(tag) => {
+                if let Some(index) = self.edit_tags.iter().position(|t| *t == tag) {
+                    self.edit_tags.remove(index);
+                } else {
+                    self.edit_tags.push(tag);
+                }
+            }
+            HistoryMsg::OnSave => {
+                if let Some(ts) = self.editing.take() {
+                    if let Some(session) = self.sessions.iter().find(|s| s.ts == ts) {
+                        self.undo = Some(Undo::Edited(session.clone()));
+                    }
+                    let note = self.edit_note.trim().to_string();
+                    let tags = std::mem::take(&mut self.edit_tags);
+                    self.db.update_session(&ts, self.edit_score, note, tags);
+                    self.load_sessions();
+                }
+            }
+            HistoryMsg::OnCancel => {
+                self.editing = None;
+            }
+            HistoryMsg::OnDelete(ts) => {
+                self.editing = None;
+                if let Some(session) = self.db.delete_session(&ts) {
+                    self.undo = Some(Undo::Deleted(session));
+                }
+                self.load_sessions();
+            }
+            HistoryMsg::OnUndo => {
+                match self.undo.take() {
+                    Some(Undo::Edited(session)) => {
+                        self.db.update_session(&session.ts, session.score, session.note, session.tags);
+                    }
+                    Some(Undo::Deleted(session)) => {
+                        self.db.restore_session(session);
+                    }
+                    None => {}
+                }
+                self.load_sessions();
+            }
+        }
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let back_icon_url = platform_url("assets/icons/back.svg");
+
+        // Group sessions by day keeping the order
+        let mut days: Vec<(&str, Vec<&SavedSession>)> = Vec::new();
+        for session in &self.sessions {
+            match days.last_mut() {
+                Some((date, group)) if *date == session.date => group.push(session),
+                _ => days.push((&session.date, vec![session])),
+            }
+        }
+
+        let session_view = |session: &SavedSession| -> Html {
+            let minutes = session.minute_of_day();
+            let time = format!("{:02}:{:02}", minutes / 60, minutes % 60);
+            let ts = session.ts.clone();
+
+            if self.editing.as_ref() == Some(&session.ts) {
+                // Tags removed in settings stay on a session until unselected
+                let mut tags = self.tags.clone();
+                tags.extend(session.tags.iter().filter(|t| !self.tags.contains(t)).cloned());
+                html! {
+                    <li class="history-session editing">
+                        <div class="history-session-row">
+                            <span>{ time }</span>
+                            <span>{ format!("{} min", session.duration) }</span>
+                        </div>
+                        <div class="history-score">
+                        { (0..=5)
+                            .map(|i| {
+                                let class = if i == self.edit_score { "selected" } else { "" };
+                                html! {
+                                    <button {class}
+                                        onclick={ctx.link().callback(move |_| HistoryMsg::OnScoreChange(i))}
+                                    >
+                                        { i }
+                                    </button>
+                                }
+                            })
+                            .collect::<Html>()
+                        }
+                        </div>
+                        <textarea
+                            ref={self.note_input.clone()}
+                            placeholder="Note"
+                            value={self.edit_note.clone()}
+                            oninput={ctx.link().callback(|_| HistoryMsg::OnNoteInput)}
+                        />
+                        if !tags.is_empty() {
+                            <div class="tags">
+                            { tags
+                                .into_iter()
+                                .map(|tag| {
+                                    let class = if self.edit_tags.contains(&tag) { "tag selected" } else { "tag" };
+                                    let title = tag.clone();
+                                    html! {
+                                        <button {class} onclick={ctx.link().callback(move |_| HistoryMsg::OnTagToggle(tag.clone()))}>
+                                            { title }
+                                        </button>
+                                    }
+                                })
+                                .collect::<Html>()
+                            }
+                            </div>
+                        }
+                        <div class="modal-buttons">
+                            <button onclick={ctx.link().callback(|_| HistoryMsg::OnCancel)}>{ "Cancel" }</button>
+                            <button onclick={ctx.link().callback(|_| HistoryMsg::OnSave)}>{ "Save" }</button>
+                        </div>
+                    </li>
+                }
+            } else {
+                let edit_ts = ts.clone();
+                html! {
+                    <li class="history-session">
+                        <div class="history-session-row">
+                            <span>{ time }</span>
+                            <span>{ format!("{} min", session.duration) }</span>
+                            <strong>{ format!("{}/5", session.score) }</strong>
+                            <span class="history-actions">
+                                <button class="text-button"
+                                    onclick={ctx.link().callback(move |_| HistoryMsg::OnEdit(edit_ts.clone()))}
+                                >
+                                    { "Edit" }
+                                </button>
+                                <button class="text-button"
+                                    onclick={ctx.link().callback(move |_| HistoryMsg::OnDelete(ts.clone()))}
+                                >
+                                    { "Delete" }
+                                </button>
+                            </span>
+                        </div>
+                        if !session.tags.is_empty() {
+                            <div class="tags">
+                            { session.tags
+                                .iter()
+                                .map(|tag| html! { <span class="tag">{ tag }</span> })
+                                .collect::<Html>()
+                            }
+                            </div>
+                        }
+                        if !session.note.is_empty() {
+                            <div class="hint">{ session.note.clone() }</div>
+                        }
+                    </li>
+                }
+            }
+        };
+
+        html! {
+            <>
+                <header>
+                    <button onclick={ctx.link().callback(|_| HistoryMsg::OnBackButton)}>
+                        <img src={back_icon_url} />
+                    </button>
+                    <h1>{ "History" }</h1>
+                </header>
+                <main class="history">
+                    if let Some(undo) = &self.undo {
+                        <section class="history-undo">
+                            <span>
+                            { match undo {
+                                Undo::Edited(_) => "Session updated",
+                                Undo::Deleted(_) => "Session deleted",
+                            } }
+                            </span>
+                            <button class="text-button"
+                                onclick={ctx.link().callback(|_| HistoryMsg::OnUndo)}
+                            >
+                                { "Undo" }
+                            </button>
+                        </section>
+                    }
+                    if days.is_empty() {
+                        <section>{ "No sessions yet" }</section>
+                    }
+                    { days
+                        .into_iter()
+                        .map(|(date, group)| html! {
+                            <section>
+                                <h3>{ date }</h3>
+                                <ul>
+                                    { group.into_iter().map(session_view).collect::<Html>() }
+                                </ul>
+                            </section>
+                        })
+                        .collect::<Html>()
+                    }
+                </main>
+            </>
+        }
+    }
+}
@@ -1,38 +1,59 @@
 use gloo_events::EventListener;
-use gloo_timers::callback::Interval;
-use js_sys::Date;
+use gloo_timers::callback::{Interval, Timeout};
+use js_sys::{Date, Promise};
 #[cfg(cordova)]
 use wasm_bindgen::prelude::*;
-use wasm_bindgen::{closure::Closure, JsCast};
-use web_sys::{CustomEvent, HtmlMediaElement};
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+use web_sys::{CustomEvent, GainNode, HtmlMediaElement, HtmlSelectElement};
+use yew::html::Scope;
 use yew::prelude::*;
 use yew_router::prelude::*;
 
 use crate::db::{Db, Session};
 use crate::app::{
-    is_android, Route, Sound, VolumeLevel,
+    is_android, Route, Sound, Volume,
     INITIAL_DURATION, MAX_DURATION, MIN_ACTIVE_SESSION,
 };
 #[cfg(cordova)]
-use crate::app::{get_audio_mode, set_audio_mode};
+use crate::app::{get_audio_mode, platform_url, set_audio_mode};
 use crate::app::components::{
     button::Button,
     charts::ScoreChart,
+    goal_ring::GoalRing,
     main_button::MainButton,
-    rating_modal::RatingModal,
+    modal::Modal,
+    rating_modal::{Rating, RatingModal},
     section_title::SectionTitle,
     session_controls::SessionControls,
+    visual_cue::VisualCue,
 };
+use crate::app::ambient::Ambient;
+use crate::app::sounds::{find_sound, BellSound, FINAL_SOUNDS, INTERMEDIATE_SOUNDS};
+use crate::app::speech::{self, Announcement};
+use crate::app::synth::Synth;
+use crate::app::vibration;
+use crate::goals::{Goal, GoalEvent, GoalProgress};
 use crate::rsg::generate_random_signals;
+use crate::settings::{
+    AmbientSettings, ChartOverlays, ClosingSequence, SignalModality, SpeechSettings, SynthSettings,
+    VisualSignal,
+};
+use crate::stats::{Granularity, PeriodAvg};
+
+/// The longest time a bell is started ahead of its tick in ms
+const MAX_LEAD: f64 = 900.0;
 
 #[cfg(cordova)]
 #[wasm_bindgen(raw_module = "/android_asset/www/js/aux.js")]
 extern "C" {
-    #[wasm_bindgen(js_name = playDing)]
-    fn play_ding(volume: f64);
+    #[wasm_bindgen(js_name = setMedia)]
+    fn set_media(intermediate_file: &str, intermediate_rate: f64, final_file: &str, final_rate: f64);
 
-    #[wasm_bindgen(js_name = playBowl)]
-    fn play_bowl(volume: f64);
+    #[wasm_bindgen(js_name = playIntermediate)]
+    fn play_intermediate(volume: f64);
+
+    #[wasm_bindgen(js_name = playFinal)]
+    fn play_final(volume: f64);
 
     #[wasm_bindgen(js_name = startForegroundService)]
     fn start_foreground_service();
@@ -61,25 +82,58 @@ pub enum Msg {
     OnSettingsPauseButtonPress,
     OnDelayChange(usize),
     OnDurationChange(usize),
-    OnSessionRated(usize),
+    OnSessionRated(Rating),
+    OnGoalModalClose,
+    OnAudioProblem(String),
+    OnAudioWarningClose(bool),
+    OnTagFilterChange,
+    OnOverlaysChange(ChartOverlays),
+    OnGranularityChange(Granularity),
     ReduceTimer,
     StopSession,
+    Signal(Sound),
+    Announce(Announcement),
+    ScheduleBell,
+    OnBellStart,
+    ClosingStrike(u32),
+    OnClosingOver,
     PlaySound(Sound),
     OnAppPause,
     OnAppResume,
 }
 
+/// Signal bell started ahead of its tick to make up for playback latency
+struct ScheduledBell {
+    /// Time (ms since epoch) the bell should be heard at
+    planned: f64,
+    /// Time (ms since epoch) the bell is heard at, known once it's started
+    actual: Option<f64>,
+    /// Timer starting a sound file
+    _timeout: Option<Timeout>,
+    /// Synthesised tone waiting on the audio clock
+    tone: Option<GainNode>,
+}
+
+impl ScheduledBell {
+    /// Keeps the bell from sounding.
+    fn cancel(self) {
+        if let Some(tone) = self.tone {
+            let _ = tone.disconnect();
+        }
+    }
+}
+
 #[derive(Properties, PartialEq)]
 pub struct HomeProps {
-    pub volume: VolumeLevel,
+    pub volume: Volume,
     pub dnd: bool,
 }
 
 pub struct Home {
     /// App database
     db: Db,
-    /// Current session Date
-    session_date: Option<Date>,
+    /// Current session
+    session: Option<Session>,
     /// Timer Interval
     interval: Option<Interval>,
     /// Active session delay
@@ -96,10 +150,58 @@ pub struct Home {
     time_remaining: usize,
     /// Rating modal visibility
     rating_modal: bool,
-    /// Ding sound ref
-    ding_sound: NodeRef,
-    /// Bowl sound ref
-    bowl_sound: NodeRef,
+    /// Goals reached or missed by the last session, shown one by one
+    goal_events: Vec<GoalEvent>,
+    /// Warning shown if bells may not be heard
+    audio_warning: Option<String>,
+    /// User defined session tags
+    tags: Vec<String>,
+    /// Tag to filter the chart by
+    tag_filter: Option<String>,
+    /// Tag filter select ref
+    tag_filter_input: NodeRef,
+    /// Lines drawn over the chart
+    overlays: ChartOverlays,
+    /// Chart aggregation period
+    granularity: Granularity,
+    /// Average scores shown on the chart
+    points: Vec<PeriodAvg>,
+    /// Goals with their progress in a current period
+    goals: Vec<(Goal, GoalProgress)>,
+    /// Selected sound of signal bells
+    intermediate_sound: &'static BellSound,
+    /// Selected sound of a session end
+    final_sound: &'static BellSound,
+    /// Whether signals are given by sound, vibration or both
+    modality: SignalModality,
+    /// Visual cue settings
+    visual: VisualSignal,
+    /// Last visual cue and a number of cues shown
+    visual_cue: Option<(Sound, usize)>,
+    /// Background soundscape settings
+    ambient_settings: AmbientSettings,
+    /// Background soundscape playing during a session
+    ambient: Option<Ambient>,
+    /// Final strikes and a rest before rating a session
+    closing: ClosingSequence,
+    /// Gain factor of a current closing strike
+    closing_gain: f64,
+    /// Pending closing strikes and the rating prompt
+    closing_timeouts: Vec<Timeout>,
+    /// Spoken announcements settings
+    speech: SpeechSettings,
+    /// Calibrated playback latency in ms
+    latency: Option<u32>,
+    /// Next signal bell if it's started ahead
+    scheduled_bell: Option<ScheduledBell>,
+    /// Tone synthesiser used instead of sound files if enabled
+    synth: Option<Synth>,
+    /// Tones of the synthesiser
+    synth_settings: SynthSettings,
+    /// Intermediate sound ref
+    intermediate_sound_ref: NodeRef,
+    /// Final sound ref
+    final_sound_ref: NodeRef,
     /// Android user audio mode
     audio_mode: usize,
     /// App global event listeners
@@ -161,10 +263,33 @@ impl Component for Home {
         let db = Db::new();
         let delay = db.get_active_session_delay();
         let duration = db.get_session_duration();
+        let tags = db.get_tags();
+        let overlays = db.get_chart_overlays();
+        let granularity = db.get_chart_granularity();
+        let points = db.get_period_avgs(None, granularity);
+        let goals = db.get_goals_progress();
+        let (intermediate_id, final_id) = db.get_bell_sounds();
+        let intermediate_sound = find_sound(INTERMEDIATE_SOUNDS, &intermediate_id);
+        let final_sound = find_sound(FINAL_SOUNDS, &final_id);
+        let modality = db.get_signal_modality();
+        let visual = db.get_visual_signal();
+        let ambient_settings = db.get_ambient_settings();
+        let closing = db.get_closing_sequence();
+        let speech = db.get_speech_settings();
+        let latency = db.get_latency();
+        let synth_settings = db.get_synth_settings();
+        let synth = synth_settings.enabled.then(Synth::new);
+        #[cfg(cordova)]
+        set_media(
+            &platform_url(&intermediate_sound.url()),
+            intermediate_sound.rate,
+            &platform_url(&final_sound.url()),
+            final_sound.rate,
+        );
 
         Self {
             db,
-            session_date: None,
+            session: None,
             interval: None,
             delay,
             duration,
@@ -173,8 +298,32 @@ impl Component for Home {
             signals: vec![],
             time_remaining: INITIAL_DURATION,
             rating_modal: false,
-            ding_sound: NodeRef::default(),
-            bowl_sound: NodeRef::default(),
+            goal_events: Vec::new(),
+            audio_warning: None,
+            tags,
+            tag_filter: None,
+            tag_filter_input: NodeRef::default(),
+            overlays,
+            granularity,
+            points,
+            goals,
+            intermediate_sound,
+            final_sound,
+            modality,
+            visual,
+            visual_cue: None,
+            ambient_settings,
+            ambient: None,
+            closing,
+            closing_gain: 1.0,
+            closing_timeouts: Vec::new(),
+            speech,
+            latency,
+            scheduled_bell: None,
+            synth,
+            synth_settings,
+            intermediate_sound_ref: NodeRef::default(),
+            final_sound_ref: NodeRef::default(),
             audio_mode: 0,
             _event_listeners: listeners,
         }
@@ -186,12 +335,53 @@ impl Component for Home {
                 self.audio_mode = val;
             }
             Msg::OnMainButtonPress => {
-                // Register session start time
-                self.session_date = Some(Date::new_0());
+                // A session being closed isn't rated yet, so skip its rest instead
+                if !self.closing_timeouts.is_empty() {
+                    ctx.link().send_message(Msg::OnClosingOver);
+                    return false;
+                }
+                // Register session start time and parameters
+                let mut session = Session::new(Date::new_0(), self.duration, self.delay);
+                if let Some(synth) = &self.synth {
+                    // The context may have been created before a user gesture
+                    warn_on_rejection(&synth.resume(), ctx.link());
+                    session.intermediate_sound = "synth-bell".to_string();
+                    session.final_sound = "synth-bowl".to_string();
+                } else {
+                    session.intermediate_sound = self.intermediate_sound.id.to_string();
+                    session.final_sound = self.final_sound.id.to_string();
+                }
+                session.volume = ctx.props().volume.master as f64 / 100.0;
+                session.dnd = ctx.props().dnd;
+                session.modality = self.modality.id().to_string();
+                session.latency = self.latency().map(|latency| latency.round() as u32);
+                self.session = Some(session);
+
+                // Make sure bells can be heard before eyes are closed.
+                // Cordova `Media` doesn't report problems, so only web sounds are checked.
+                if self.modality.has_sound() && self.synth.is_none() && !is_android() {
+                    let loaded = [&self.intermediate_sound_ref, &self.final_sound_ref]
+                        .iter()
+                        .filter_map(|sound_ref| sound_ref.cast::<HtmlMediaElement>())
+                        .all(|sound| sound.ready_state() >= HtmlMediaElement::HAVE_FUTURE_DATA);
+                    if !loaded {
+                        ctx.link().send_message(Msg::OnAudioProblem(
+                            "Bell sounds haven't loaded yet, so you may not hear them.".to_string()
+                        ));
+                    }
+                }
+
+                if let Some(sound) = self.ambient_settings.sound {
+                    let volume = self.ambient_settings.volume as f64 / 100.0
+                        * ctx.props().volume.master as f64 / 100.0;
+                    // There's no background without Web Audio
+                    self.ambient = Ambient::start(sound, volume);
+                }
 
                 self.signals = generate_random_signals(self.duration - self.delay, self.delay);
                 let scope = ctx.link().clone();
-                scope.send_message(Msg::PlaySound(Sound::Ding));
+                scope.send_message(Msg::Signal(Sound::Opening));
+                scope.send_message(Msg::Announce(Announcement::Start));
                 self.in_session = true;
                 self.time_remaining = self.duration;
                 let interval = Interval::new(1_000, move || {
@@ -212,7 +402,7 @@ impl Component for Home {
                 if self.in_session {
                     let scope = ctx.link().clone();
                     scope.send_message(Msg::StopSession);
-                } else {
+                } else if self.closing_timeouts.is_empty() {
                     let navigator = ctx.link().navigator().unwrap();
                     navigator.push(&Route::About);
                 }
@@ -220,7 +410,21 @@ impl Component for Home {
             Msg::OnSettingsPauseButtonPress => {
                 if self.in_session {
                     self.is_paused = !self.is_paused;
-                } else {
+                    if let Some(bell) = self.scheduled_bell.take() {
+                        bell.cancel();
+                    }
+                    if let Some(session) = self.session.as_mut() {
+                        if self.is_paused {
+                            session.pause();
+                        } else {
+                            session.resume();
+                        }
+                    }
+                    if let Some(ambient) = &self.ambient {
+                        ambient.set_paused(self.is_paused);
+                    }
+                } else if self.closing_timeouts.is_empty() {
+                    // Leaving a closing session would lose it before it's rated
                     let navigator = ctx.link().navigator().unwrap();
                     navigator.push(&Route::Settings);
                 }
@@ -255,30 +459,143 @@ impl Component for Home {
                 self.db.set_session_duration(self.duration);
                 self.db.set_active_session_delay(self.delay);
             }
-            Msg::OnSessionRated(value) => {
+            Msg::OnSessionRated(rating) => {
                 self.rating_modal = false;
-                let session_date = self.session_date.take().unwrap();
-                let session = Session::new(session_date, self.duration, value);
-                self.db.add_session(session);
+                let mut session = self.session.take().unwrap();
+                session.score = rating.score;
+                session.note = rating.note;
+                session.tags = rating.tags;
+                self.goal_events = self.db.add_session(session);
+                self.points = self.db.get_period_avgs(self.tag_filter.as_deref(), self.granularity);
+                self.goals = self.db.get_goals_progress();
+            }
+            Msg::OnGoalModalClose => {
+                if !self.goal_events.is_empty() {
+                    self.goal_events.remove(0);
+                }
+            }
+            Msg::OnAudioProblem(text) => {
+                // Wait for the user to decide
+                if self.in_session && !self.is_paused {
+                    self.is_paused = true;
+                    if let Some(bell) = self.scheduled_bell.take() {
+                        bell.cancel();
+                    }
+                    if let Some(session) = self.session.as_mut() {
+                        session.pause();
+                    }
+                    if let Some(ambient) = &self.ambient {
+                        ambient.set_paused(true);
+                    }
+                }
+                self.audio_warning = Some(text);
+            }
+            Msg::OnAudioWarningClose(proceed) => {
+                self.audio_warning = None;
+                if !self.in_session {
+                    return true;
+                }
+                if proceed {
+                    self.is_paused = false;
+                    if let Some(session) = self.session.as_mut() {
+                        session.resume();
+                    }
+                    if let Some(ambient) = &self.ambient {
+                        ambient.set_paused(false);
+                    }
+                    // A tap allows a browser to play sounds, so try again
+                    if let Some(synth) = &self.synth {
+                        let _ = synth.resume();
+                    }
+                    ctx.link().send_message(Msg::PlaySound(Sound::Opening));
+                } else {
+                    ctx.link().send_message(Msg::StopSession);
+                }
+            }
+            Msg::OnTagFilterChange => {
+                if let Some(input) = self.tag_filter_input.cast::<HtmlSelectElement>() {
+                    let value = input.value();
+                    self.tag_filter = if value.is_empty() { None } else { Some(value) };
+                }
+                self.points = self.db.get_period_avgs(self.tag_filter.as_deref(), self.granularity);
+            }
+            Msg::OnOverlaysChange(overlays) => {
+                self.db.set_chart_overlays(&overlays);
+                self.overlays = overlays;
+            }
+            Msg::OnGranularityChange(granularity) => {
+                self.db.set_chart_granularity(granularity);
+                self.granularity = granularity;
+                self.points = self.db.get_period_avgs(self.tag_filter.as_deref(), granularity);
             }
             Msg::ReduceTimer => {
                 if !self.is_paused {
                     self.time_remaining -= 1;
                     let time_elapsed = self.duration - self.time_remaining;
+                    if self.signals.contains(&time_elapsed) {
+                        let now = Date::now();
+                        let (planned, actual) = match &self.scheduled_bell {
+                            Some(ScheduledBell { planned, actual: Some(actual), .. }) => (*planned, *actual),
+                            _ => (now, now + self.latency().unwrap_or(0.0)),
+                        };
+                        if let Some(session) = self.session.as_mut() {
+                            session.ring(planned, actual);
+                        }
+                    }
                     // Play ding sound for all signals except of the last
                     if self.signals[0..self.signals.len() - 1].contains(&time_elapsed) {
                         let scope = ctx.link().clone();
-                        scope.send_message(Msg::PlaySound(Sound::Ding));
+                        scope.send_message(Msg::Signal(Sound::Intermediate));
+                    }
+                    // Queued after a signal of this tick so it doesn't take the new bell
+                    if self.signals[0..self.signals.len() - 1].contains(&(time_elapsed + 1)) {
+                        ctx.link().send_message(Msg::ScheduleBell);
+                    }
+                    // Announcements follow the same timer as bells so pauses shift them too
+                    if self.delay > 0 && time_elapsed == self.delay {
+                        ctx.link().send_message(Msg::Announce(Announcement::DelayOver));
+                    }
+                    if time_elapsed == speech::halfway(self.delay, self.duration) && time_elapsed > self.delay {
+                        ctx.link().send_message(Msg::Announce(Announcement::Halfway));
                     }
                     if self.time_remaining == 0 {
+                        if let Some(session) = self.session.as_mut() {
+                            session.completed = true;
+                        }
                         let scope = ctx.link().clone();
                         scope.send_message(Msg::StopSession);
-                        scope.send_message(Msg::PlaySound(Sound::Bowl));
-                        self.rating_modal = true;
+                        scope.send_message(Msg::ClosingStrike(0));
+                        for strike in 1..self.closing.strikes {
+                            let scope = scope.clone();
+                            self.closing_timeouts.push(Timeout::new(strike * self.closing.spacing * 1_000, move || {
+                                scope.send_message(Msg::ClosingStrike(strike));
+                            }));
+                        }
+                        match self.closing.length() {
+                            0 => scope.send_message(Msg::OnClosingOver),
+                            length => self.closing_timeouts.push(Timeout::new(length * 1_000, move || {
+                                scope.send_message(Msg::OnClosingOver);
+                            })),
+                        }
                     }
                 }
             }
             Msg::StopSession => {
+                if self.is_paused {
+                    if let Some(session) = self.session.as_mut() {
+                        session.resume();
+                    }
+                }
+                // A session stopped early is not saved
+                if self.session.as_ref().is_some_and(|session| !session.completed) {
+                    self.session = None;
+                }
+                if let Some(ambient) = self.ambient.take() {
+                    ambient.stop();
+                }
+                if let Some(bell) = self.scheduled_bell.take() {
+                    bell.cancel();
+                }
                 self.interval = None;
                 self.in_session = false;
                 self.is_paused = false;
@@ -291,29 +608,106 @@ impl Component for Home {
                     set_audio_mode(self.audio_mode);
                 }
             }
+            Msg::Signal(sound) => {
+                if self.modality.has_vibration() {
+                    vibration::vibrate(sound);
+                }
+                // A bell that hasn't started ahead is played right away (dropping it stops its timer)
+                let bell = self.scheduled_bell.take();
+                let started = bell.as_ref().is_some_and(|bell| bell.actual.is_some());
+                // A sound file started ahead has lowered a background already
+                let ducked = started && bell.is_some_and(|bell| bell.tone.is_none());
+                if self.modality.has_sound() {
+                    if let Some(ambient) = self.ambient.as_ref().filter(|_| !ducked) {
+                        ambient.duck();
+                    }
+                    if !started {
+                        ctx.link().send_message(Msg::PlaySound(sound));
+                    }
+                }
+                if !self.visual.enabled {
+                    return false;
+                }
+                let count = self.visual_cue.map_or(0, |(_, count)| count);
+                self.visual_cue = Some((sound, count + 1));
+            }
+            Msg::ScheduleBell => {
+                if self.in_session && !self.is_paused {
+                    self.schedule_bell(ctx);
+                }
+                return false;
+            }
+            Msg::OnBellStart => {
+                let latency = self.latency().unwrap_or(0.0);
+                if let Some(bell) = self.scheduled_bell.as_mut() {
+                    bell.actual = Some(Date::now() + latency);
+                }
+                if let Some(ambient) = &self.ambient {
+                    ambient.duck();
+                }
+                ctx.link().send_message(Msg::PlaySound(Sound::Intermediate));
+                return false;
+            }
+            Msg::ClosingStrike(strike) => {
+                self.closing_gain = self.closing.strike_gain(strike);
+                ctx.link().send_message(Msg::Signal(Sound::Final));
+                return false;
+            }
+            Msg::OnClosingOver => {
+                // Dropping timeouts cancels strikes left if the rest is skipped
+                self.closing_timeouts.clear();
+                self.rating_modal = true;
+                ctx.link().send_message(Msg::Announce(Announcement::Complete));
+            }
+            Msg::Announce(announcement) => {
+                if !self.speech.enabled {
+                    return false;
+                }
+                if let Some(ambient) = &self.ambient {
+                    ambient.duck();
+                }
+                speech::announce(announcement, &self.speech, ctx.props().volume.master as f64 / 100.0);
+                return false;
+            }
             Msg::PlaySound(sound) => {
-                let volume = ctx.props().volume.numeric_value();
+                let mut volume = ctx.props().volume.gain(sound);
+                if sound == Sound::Final {
+                    volume *= self.closing_gain;
+                }
+                if let Some(synth) = &self.synth {
+                    synth.play(sound, self.synth_settings.tone(sound), volume);
+                    return false;
+                }
                 #[cfg(cordova)]
                 match sound {
-                    Sound::Ding => play_ding(volume),
-                    Sound::Bowl => play_bowl(volume),
+                    Sound::Opening | Sound::Intermediate => play_intermediate(volume),
+                    Sound::Final => play_final(volume),
                 }
                 if !is_android() {
-                    let sound_ref = match sound {
-                        Sound::Ding => self.ding_sound.clone(),
-                        Sound::Bowl => self.bowl_sound.clone(),
+                    let (sound_ref, bell_sound) = match sound {
+                        Sound::Opening | Sound::Intermediate => (self.intermediate_sound_ref.clone(), self.intermediate_sound),
+                        Sound::Final => (self.final_sound_ref.clone(), self.final_sound),
                     };
-                    let sound = sound_ref
+                    let media = sound_ref
                         .cast::<HtmlMediaElement>()
                         .unwrap();
-                    sound.set_volume(volume);
-                    let _ = sound.play().expect("Unable to play sound");
+                    bell_sound.apply_rate(&media);
+                    media.set_volume(volume);
+                    let promise = media.play().expect("Unable to play sound");
+                    if sound == Sound::Opening {
+                        warn_on_rejection(&promise, ctx.link());
+                    }
                  }
             }
             Msg::OnAppPause => {
+                // The service keeps the WebView running, so Web Audio
+                // (synthesised bells and the background) keeps playing
                 #[cfg(cordova)]
                 if self.in_session {
                     start_foreground_service();
+                    if let Some(ambient) = &self.ambient {
+                        ambient.keep_playing();
+                    }
                 }
             }
             Msg::OnAppResume => {
@@ -325,15 +719,123 @@ impl Component for Home {
     }
 
     fn view(&self, ctx: &Context<Self>) -> Html {
+        let granularity = self.granularity;
+        let points = self.points.clone();
+        let granularity_toggle = |title: &str, value: Granularity| {
+            let class = if value == granularity { "tag selected" } else { "tag" };
+            html! {
+                <button {class} onclick={ctx.link().callback(move |_| Msg::OnGranularityChange(value))}>
+                    { title }
+                </button>
+            }
+        };
+        let overlay_toggle = |title: &str, enabled: bool, toggled: ChartOverlays| {
+            let class = if enabled { "tag selected" } else { "tag" };
+            html! {
+                <button {class} onclick={ctx.link().callback(move |_| Msg::OnOverlaysChange(toggled))}>
+                    { title }
+                </button>
+            }
+        };
+        let overlays = self.overlays;
+        let goals = &self.goals;
+        let closing = !self.closing_timeouts.is_empty();
+        let (goal_modal_title, goal_modal_text) = match self.goal_events.first() {
+            Some(event) if event.reached => (
+                "Goal Reached".to_string(),
+                format!("Well done! You have reached your goal: {}", event.goal.title().to_lowercase()),
+            ),
+            Some(event) => (
+                "Goal Missed".to_string(),
+                format!(
+                    "Your goal \"{}\" was missed with {}. Keep practising!",
+                    event.goal.title().to_lowercase(),
+                    event.goal.format_value(event.progress.value),
+                ),
+            ),
+            None => (String::new(), String::new()),
+        };
+
         html! {
             <main>
                 <section class="chart">
                     <SectionTitle
-                        title={"Daily Average Score"}
+                        title={"Average Score"}
                         hint={"This chart helps you assess how your attention develops"}
                     />
-                    <ScoreChart avgs={self.db.get_avgs()} />
+                    <ScoreChart {points} {granularity} {overlays} />
+                    <div class="chart-overlays">
+                        { granularity_toggle("Day", Granularity::Day) }
+                        { granularity_toggle("Week", Granularity::Week) }
+                        { granularity_toggle("Month", Granularity::Month) }
+                    </div>
+                    <div class="chart-overlays">
+                        { overlay_toggle(
+                            "Rolling mean",
+                            overlays.rolling_mean,
+                            ChartOverlays { rolling_mean: !overlays.rolling_mean, ..overlays },
+                        ) }
+                        { overlay_toggle(
+                            "EWMA",
+                            overlays.ewma,
+                            ChartOverlays { ewma: !overlays.ewma, ..overlays },
+                        ) }
+                        { overlay_toggle(
+                            "Trend",
+                            overlays.trend,
+                            ChartOverlays { trend: !overlays.trend, ..overlays },
+                        ) }
+                    </div>
+                    <div class="chart-links">
+                        if !self.tags.is_empty() {
+                            <select
+                                ref={self.tag_filter_input.clone()}
+                                onchange={ctx.link().callback(|_| Msg::OnTagFilterChange)}
+                            >
+                                <option value="" selected={self.tag_filter.is_none()}>{ "All sessions" }</option>
+                                { self.tags
+                                    .iter()
+                                    .map(|tag| html! {
+                                        <option
+                                            value={tag.clone()}
+                                            selected={self.tag_filter.as_ref() == Some(tag)}
+                                        >
+                                            { tag }
+                                        </option>
+                                    })
+                                    .collect::<Html>()
+                                }
+                            </select>
+                        }
+                        if !closing {
+                            <Link<Route> to={Route::Stats}>{ "Statistics" }</Link<Route>>
+                            <Link<Route> to={Route::History}>{ "History" }</Link<Route>>
+                            <Link<Route> to={Route::Achievements}>{ "Achievements" }</Link<Route>>
+                        }
+                    </div>
                 </section>
+                if !goals.is_empty() {
+                    <section class="goals">
+                        <SectionTitle
+                            title={"Goals"}
+                            hint={"Progress of your practice goals over the current day, week or month"}
+                        />
+                        <div class="goal-rings">
+                        { goals
+                            .iter()
+                            .map(|(goal, progress)| html! {
+                                <GoalRing
+                                    title={goal.title()}
+                                    value={goal.format_value(progress.value)}
+                                    fraction={progress.fraction}
+                                    reached={progress.reached}
+                                />
+                            })
+                            .collect::<Html>()
+                        }
+                        </div>
+                    </section>
+                }
                 <section class="session">
                     <SectionTitle
                         title={"Session Parameters"}
@@ -361,6 +863,7 @@ impl Component for Home {
                     <Button icon="help"
                         alt_icon="stop"
                         in_session={self.in_session}
+                        disabled={closing}
                         on_click={ctx.link().callback(|_| Msg::OnHelpStopButtonPress)}
                     />
                     <MainButton
@@ -372,18 +875,94 @@ impl Component for Home {
                     <Button icon="settings"
                         alt_icon="pause"
                         in_session={self.in_session}
+                        disabled={closing}
                         on_click={ctx.link().callback(|_| Msg::OnSettingsPauseButtonPress)}
                     />
                 </section>
+                if closing {
+                    <div class="rest-notice">
+                        { "Closing the session" }
+                        <button class="text-button" onclick={ctx.link().callback(|_| Msg::OnClosingOver)}>
+                            { "Skip" }
+                        </button>
+                    </div>
+                }
                 <RatingModal
                     visible={self.rating_modal}
+                    tags={self.tags.clone()}
                     callback={ctx.link().callback(|val| Msg::OnSessionRated(val))}
                 />
-                if !is_android() {
-                    <audio ref={self.ding_sound.clone()} src="assets/sounds/ding.ogg" />
-                    <audio ref={self.bowl_sound.clone()} src="assets/sounds/bowl.ogg" />
+                <Modal
+                    title={"Sound Problem"}
+                    text={format!(
+                        "{} Press Ok to continue the session or Cancel to stop it.",
+                        self.audio_warning.clone().unwrap_or_default(),
+                    )}
+                    visible={self.audio_warning.is_some()}
+                    callback={ctx.link().callback(Msg::OnAudioWarningClose)}
+                />
+                <Modal
+                    title={goal_modal_title}
+                    text={goal_modal_text}
+                    visible={!self.goal_events.is_empty()}
+                    callback={ctx.link().callback(|_| Msg::OnGoalModalClose)}
+                />
+                if let Some((sound, count)) = self.visual_cue {
+                    <VisualCue {sound} {count} settings={self.visual} />
+                }
+                if !is_android() && self.synth.is_none() {
+                    <audio ref={self.intermediate_sound_ref.clone()} src={self.intermediate_sound.url()} preload="auto" />
+                    <audio ref={self.final_sound_ref.clone()} src={self.final_sound.url()} preload="auto" />
                 }
             </main>
         }
     }
 }
+
+impl Home {
+    /// Playback latency in ms: reported by Web Audio or calibrated.
+    /// It's unknown for uncalibrated sound files.
+    fn latency(&self) -> Option<f64> {
+        match &self.synth {
+            Some(synth) => Some(synth.latency()),
+            None => self.latency.map(|latency| latency as f64),
+        }
+    }
+
+    /// Starts a signal bell due at the next tick ahead by the playback latency.
+    fn schedule_bell(&mut self, ctx: &Context<Self>) {
+        let Some(latency) = self.latency().filter(|_| self.modality.has_sound()) else {
+            return;
+        };
+        let lead = latency.clamp(0.0, MAX_LEAD);
+        // Ticks are a second apart
+        let delay = 1_000.0 - lead;
+        let planned = Date::now() + 1_000.0;
+        let mut bell = ScheduledBell { planned, actual: None, _timeout: None, tone: None };
+        if let Some(synth) = &self.synth {
+            // The audio clock is more precise than timers
+            let volume = ctx.props().volume.gain(Sound::Intermediate);
+            let tone = self.synth_settings.tone(Sound::Intermediate);
+            bell.tone = Some(synth.play_in(Sound::Intermediate, tone, volume, delay / 1_000.0));
+            bell.actual = Some(planned - lead + latency);
+        } else {
+            let scope = ctx.link().clone();
+            bell._timeout = Some(Timeout::new(delay as u32, move || {
+                scope.send_message(Msg::OnBellStart);
+            }));
+        }
+        self.scheduled_bell = Some(bell);
+    }
+}
+
+/// Warns a user if a browser refuses to play a sound (e.g. due to an autoplay policy).
+fn warn_on_rejection(promise: &Promise, link: &Scope<Home>) {
+    let scope = link.clone();
+    let on_rejected = Closure::once(move |_: JsValue| {
+        scope.send_message(Msg::OnAudioProblem(
+            "Your browser doesn't allow the app to play sounds.".to_string()
+        ));
+    });
+    let _ = promise.catch(&on_rejected);
+    on_rejected.forget();
+}
@@ -1,8 +1,24 @@
 use gloo_events::EventListener;
+use gloo_timers::callback::Timeout;
+#[cfg(cordova)]
+use wasm_bindgen::prelude::*;
+use web_sys::{FileReader, HtmlAudioElement, HtmlInputElement, HtmlSelectElement, SpeechSynthesisVoice};
 use yew::prelude::*;
 use yew_router::prelude::*;
 
-use crate::app::{Route, VolumeLevel, platform_url};
+use crate::app::{is_android, Route, Sound, Volume, platform_url};
+use crate::app::latency::{self, Calibration, RUN_SPACING};
+use crate::app::sounds::{find_sound, BellSound, FINAL_SOUNDS, INTERMEDIATE_SOUNDS};
+use crate::app::speech::{self, Announcement};
+use crate::app::synth::Synth;
+use crate::app::vibration;
+use crate::db::{Db, ImportPreview};
+use crate::goals::{Goal, GoalMetric};
+use crate::settings::{
+    AmbientSettings, AmbientSound, ClosingSequence, SignalModality, SpeechSettings, SynthSettings,
+    Tone, VisualSignal, VisualStyle,
+};
+use crate::stats::Granularity;
 #[cfg(cordova)]
 use crate::app::{has_dnd_permission, request_dnd_permission};
 use crate::app::components::{
@@ -11,10 +27,17 @@ use crate::app::components::{
     switch::Switch,
 };
 
+#[cfg(cordova)]
+#[wasm_bindgen(raw_module = "/android_asset/www/js/aux.js")]
+extern "C" {
+    #[wasm_bindgen(js_name = playPreview)]
+    fn play_preview(file: &str, rate: f64, volume: f64);
+}
+
 #[derive(Properties, PartialEq)]
 pub struct SettingsProps {
-    pub volume: VolumeLevel,
-    pub on_volume_change: Callback<VolumeLevel>,
+    pub volume: Volume,
+    pub on_volume_change: Callback<Volume>,
     pub dnd: bool,
     pub on_dnd_change: Callback<bool>,
 }
@@ -25,23 +48,114 @@ struct SettingsEventListeners {
     _dnd_not_granted: EventListener,
     _has_dnd_granted: EventListener,
     _has_dnd_not_granted: EventListener,
+    _voices_changed: Option<EventListener>,
 }
 
 pub enum SettingsMsg {
     OnBackButton,
-    OnVolumeChange(usize),
+    OnVolumeChange(Volume),
     OnDNDChange(bool),
     OnDNDPermissionGranted(bool),
     OnDNDPermissionStatus(bool),
+    OnDayStartChange,
+    OnAddTag,
+    OnRemoveTag(String),
+    OnGoalMetricChange,
+    OnAddGoal,
+    OnRemoveGoal(usize),
+    OnBellSoundChange,
+    OnPreviewSound(Sound),
+    OnSynthChange(SynthSettings),
+    OnCalibrate,
+    OnCalibrationProbe,
+    OnLatencySample(Option<f64>),
+    OnModalityChange,
+    OnVisualChange(VisualSignal),
+    OnAmbientChange(AmbientSettings),
+    OnAmbientSoundChange,
+    OnClosingChange(ClosingSequence),
+    OnSpeechChange(SpeechSettings),
+    OnSpeechLangChange,
+    OnSpeechVoiceChange,
+    OnSpeechTest,
+    OnVoicesChanged,
     OpenDNDModal,
-    CloseDNDModal,
+    OnImportButton,
+    OnImportFileSelected,
+    OnImportFileLoaded(String),
+    OnImportConfirmed(bool),
+    CloseModal,
 }
 
 pub struct Settings {
+    /// Minutes after midnight when a new day begins
+    day_start: u32,
+    /// Day start select ref
+    day_start_input: NodeRef,
+    /// User defined session tags
+    tags: Vec<String>,
+    /// New tag input ref
+    tag_input: NodeRef,
+    /// Practice goals
+    goals: Vec<Goal>,
+    /// Metric of a new goal
+    goal_metric: GoalMetric,
+    /// New goal metric select ref
+    goal_metric_input: NodeRef,
+    /// New goal target input ref
+    goal_target_input: NodeRef,
+    /// New goal period select ref
+    goal_period_input: NodeRef,
+    /// Selected sound of signal bells
+    intermediate_sound: &'static BellSound,
+    /// Selected sound of a session end
+    final_sound: &'static BellSound,
+    /// Intermediate sound select ref
+    intermediate_sound_input: NodeRef,
+    /// Final sound select ref
+    final_sound_input: NodeRef,
+    /// Sound being previewed
+    preview: Option<HtmlAudioElement>,
+    /// Whether signals are given by sound, vibration or both
+    modality: SignalModality,
+    /// Signal modality select ref
+    modality_input: NodeRef,
+    /// Visual cue settings
+    visual: VisualSignal,
+    /// Background soundscape settings
+    ambient: AmbientSettings,
+    /// Background sound select ref
+    ambient_input: NodeRef,
+    /// Final strikes and a rest before rating
+    closing: ClosingSequence,
+    /// Spoken announcements settings
+    speech: SpeechSettings,
+    /// Voices installed in a browser
+    voices: Vec<SpeechSynthesisVoice>,
+    /// Announcement language select ref
+    speech_lang_input: NodeRef,
+    /// Announcement voice select ref
+    speech_voice_input: NodeRef,
+    /// Synthesised tones settings
+    synth_settings: SynthSettings,
+    /// Tone synthesiser created with the first preview
+    synth: Option<Synth>,
+    /// Calibrated playback latency in ms
+    latency: Option<u32>,
+    /// Playback latency in ms reported by Web Audio (not stored)
+    synth_latency: Option<u32>,
+    /// Latency calibration in progress
+    calibration: Option<Calibration>,
     modal_title: String,
     modal_text: String,
     modal_visible: bool,
     modal_callback: Callback<bool>,
+    /// Hidden file input used to pick a file to import
+    import_input: NodeRef,
+    /// Listener of an import file being read
+    import_reader: Option<EventListener>,
+    /// Parsed import file waiting for user confirmation
+    import_preview: Option<ImportPreview>,
     /// App global event listeners
     _event_listeners: SettingsEventListeners,
 }
@@ -106,12 +220,51 @@ impl Component for Settings {
             _dnd_not_granted: dnd_not_granted,
             _has_dnd_granted: has_dnd_granted,
             _has_dnd_not_granted: has_dnd_not_granted,
+            _voices_changed: speech::on_voices_changed({
+                let scope = ctx.link().clone();
+                move || scope.send_message(SettingsMsg::OnVoicesChanged)
+            }),
         };
+        let (intermediate_id, final_id) = Db::new().get_bell_sounds();
+        let intermediate_sound = find_sound(INTERMEDIATE_SOUNDS, &intermediate_id);
+        let final_sound = find_sound(FINAL_SOUNDS, &final_id);
         Self {
+            day_start: Db::new().get_day_start(),
+            day_start_input: NodeRef::default(),
+            tags: Db::new().get_tags(),
+            tag_input: NodeRef::default(),
+            goals: Db::new().get_goals(),
+            goal_metric: GoalMetric::Sessions,
+            goal_metric_input: NodeRef::default(),
+            goal_target_input: NodeRef::default(),
+            goal_period_input: NodeRef::default(),
+            intermediate_sound,
+            final_sound,
+            intermediate_sound_input: NodeRef::default(),
+            final_sound_input: NodeRef::default(),
+            preview: None,
+            modality: Db::new().get_signal_modality(),
+            modality_input: NodeRef::default(),
+            visual: Db::new().get_visual_signal(),
+            ambient: Db::new().get_ambient_settings(),
+            ambient_input: NodeRef::default(),
+            closing: Db::new().get_closing_sequence(),
+            speech: Db::new().get_speech_settings(),
+            voices: speech::voices(),
+            speech_lang_input: NodeRef::default(),
+            speech_voice_input: NodeRef::default(),
+            synth_settings: Db::new().get_synth_settings(),
+            synth: None,
+            latency: Db::new().get_latency(),
+            synth_latency: None,
+            calibration: None,
             modal_title: String::new(),
             modal_text: String::new(),
             modal_visible: false,
             modal_callback: Callback::noop(),
+            import_input: NodeRef::default(),
+            import_reader: None,
+            import_preview: None,
             _event_listeners: listeners,
         }
     }
@@ -122,14 +275,8 @@ impl Component for Settings {
                 let navigator = ctx.link().navigator().unwrap();
                 navigator.push(&Route::Home);
             }
-            SettingsMsg::OnVolumeChange(val) => {
-                let level = match val {
-                    3 => VolumeLevel::Max,
-                    2 => VolumeLevel::Moderate,
-                    1 => VolumeLevel::Low,
-                    _ => unimplemented!(),
-                };
-                ctx.props().on_volume_change.emit(level);
+            SettingsMsg::OnVolumeChange(volume) => {
+                ctx.props().on_volume_change.emit(volume);
             }
             SettingsMsg::OnDNDChange(val) => {
                 if cfg!(cordova) {
@@ -155,10 +302,10 @@ impl Component for Settings {
             }
             SettingsMsg::OnDNDPermissionGranted(is_granted) => {
                 if is_granted {
-                    ctx.link().send_message(SettingsMsg::CloseDNDModal);
+                    ctx.link().send_message(SettingsMsg::CloseModal);
                     ctx.props().on_dnd_change.emit(true);
                 } else {
-                    ctx.link().send_message(SettingsMsg::CloseDNDModal);
+                    ctx.link().send_message(SettingsMsg::CloseModal);
                 }
             }
             SettingsMsg::OpenDNDModal => {
@@ -172,12 +319,259 @@ impl Component for Settings {
                             #[cfg(cordova)]
                             request_dnd_permission();
                         } else {
-                            scope.send_message(SettingsMsg::CloseDNDModal);
+                            scope.send_message(SettingsMsg::CloseModal);
                         }
                     })
                 };
             }
-            SettingsMsg::CloseDNDModal => {
+            SettingsMsg::OnDayStartChange => {
+                if let Some(input) = self.day_start_input.cast::<HtmlSelectElement>() {
+                    self.day_start = input.value().parse::<u32>().unwrap();
+                    Db::new().set_day_start(self.day_start);
+                }
+            }
+            SettingsMsg::OnAddTag => {
+                if let Some(input) = self.tag_input.cast::<HtmlInputElement>() {
+                    let tag = input.value().trim().to_lowercase();
+                    if !tag.is_empty() && !self.tags.contains(&tag) {
+                        self.tags.push(tag);
+                        Db::new().set_tags(&self.tags);
+                    }
+                    input.set_value("");
+                }
+            }
+            SettingsMsg::OnRemoveTag(tag) => {
+                self.tags.retain(|t| *t != tag);
+                Db::new().set_tags(&self.tags);
+            }
+            SettingsMsg::OnGoalMetricChange => {
+                if let Some(input) = self.goal_metric_input.cast::<HtmlSelectElement>() {
+                    self.goal_metric = match input.value().as_str() {
+                        "minutes" => GoalMetric::Minutes,
+                        "score" => GoalMetric::AvgScore,
+                        _ => GoalMetric::Sessions,
+                    };
+                }
+            }
+            SettingsMsg::OnAddGoal => {
+                let target = self.goal_target_input.cast::<HtmlInputElement>().unwrap();
+                let period = self.goal_period_input.cast::<HtmlSelectElement>().unwrap();
+                let metric = self.goal_metric;
+                let period = match period.value().as_str() {
+                    "week" => Granularity::Week,
+                    "month" => Granularity::Month,
+                    _ => Granularity::Day,
+                };
+                if let Ok(value) = target.value().parse::<f32>() {
+                    // Only an average score can have a fractional target
+                    let (max, whole) = match metric {
+                        GoalMetric::AvgScore => (5.0, false),
+                        _ => (f32::MAX, true),
+                    };
+                    if value > 0.0 && value <= max && (!whole || value.fract() == 0.0) {
+                        self.goals.push(Goal::new(metric, value, period));
+                        Db::new().set_goals(&self.goals);
+                    }
+                }
+                target.set_value("");
+            }
+            SettingsMsg::OnRemoveGoal(index) => {
+                self.goals.remove(index);
+                Db::new().set_goals(&self.goals);
+            }
+            SettingsMsg::OnBellSoundChange => {
+                if let Some(input) = self.intermediate_sound_input.cast::<HtmlSelectElement>() {
+                    self.intermediate_sound = find_sound(INTERMEDIATE_SOUNDS, &input.value());
+                }
+                if let Some(input) = self.final_sound_input.cast::<HtmlSelectElement>() {
+                    self.final_sound = find_sound(FINAL_SOUNDS, &input.value());
+                }
+                Db::new().set_bell_sounds(self.intermediate_sound.id, self.final_sound.id);
+            }
+            SettingsMsg::OnPreviewSound(sound) => {
+                let volume = ctx.props().volume.gain(sound);
+                if self.synth_settings.enabled {
+                    let synth = self.synth.get_or_insert_with(Synth::new);
+                    let _ = synth.resume();
+                    synth.play(sound, self.synth_settings.tone(sound), volume);
+                    return false;
+                }
+                let sound = match sound {
+                    Sound::Opening | Sound::Intermediate => self.intermediate_sound,
+                    Sound::Final => self.final_sound,
+                };
+                #[cfg(cordova)]
+                play_preview(&platform_url(&sound.url()), sound.rate, volume);
+                if !is_android() {
+                    if let Some(preview) = self.preview.take() {
+                        preview.pause().unwrap();
+                    }
+                    let preview = HtmlAudioElement::new_with_src(&sound.url()).unwrap();
+                    sound.apply_rate(&preview);
+                    preview.set_volume(volume);
+                    let _ = preview.play().expect("Unable to play sound");
+                    self.preview = Some(preview);
+                }
+                return false;
+            }
+            SettingsMsg::OnModalityChange => {
+                if let Some(input) = self.modality_input.cast::<HtmlSelectElement>() {
+                    self.modality = SignalModality::from_id(&input.value());
+                    Db::new().set_signal_modality(self.modality);
+                }
+            }
+            SettingsMsg::OnVisualChange(visual) => {
+                self.visual = visual;
+                Db::new().set_visual_signal(&self.visual);
+            }
+            SettingsMsg::OnAmbientChange(ambient) => {
+                self.ambient = ambient;
+                Db::new().set_ambient_settings(&self.ambient);
+            }
+            SettingsMsg::OnAmbientSoundChange => {
+                if let Some(input) = self.ambient_input.cast::<HtmlSelectElement>() {
+                    self.ambient.sound = match input.value().as_str() {
+                        "rain" => Some(AmbientSound::Rain),
+                        "brown" => Some(AmbientSound::BrownNoise),
+                        "stream" => Some(AmbientSound::Stream),
+                        _ => None,
+                    };
+                    Db::new().set_ambient_settings(&self.ambient);
+                }
+            }
+            SettingsMsg::OnClosingChange(closing) => {
+                self.closing = closing;
+                Db::new().set_closing_sequence(&self.closing);
+            }
+            SettingsMsg::OnSpeechChange(speech) => {
+                self.speech = speech;
+                Db::new().set_speech_settings(&self.speech);
+            }
+            SettingsMsg::OnSpeechLangChange => {
+                if let Some(input) = self.speech_lang_input.cast::<HtmlSelectElement>() {
+                    self.speech.lang = input.value();
+                    // A voice of another language would ignore the choice
+                    if !self.voices.iter().any(|v| v.name() == self.speech.voice && v.lang() == self.speech.lang) {
+                        self.speech.voice = String::new();
+                    }
+                    Db::new().set_speech_settings(&self.speech);
+                }
+            }
+            SettingsMsg::OnSpeechVoiceChange => {
+                if let Some(input) = self.speech_voice_input.cast::<HtmlSelectElement>() {
+                    self.speech.voice = input.value();
+                    Db::new().set_speech_settings(&self.speech);
+                }
+            }
+            SettingsMsg::OnSpeechTest => {
+                let volume = ctx.props().volume.master as f64 / 100.0;
+                speech::announce(Announcement::Start, &self.speech, volume);
+                return false;
+            }
+            SettingsMsg::OnVoicesChanged => {
+                self.voices = speech::voices();
+            }
+            SettingsMsg::OnSynthChange(synth_settings) => {
+                self.synth_settings = synth_settings;
+                Db::new().set_synth_settings(&self.synth_settings);
+            }
+            SettingsMsg::OnCalibrate => {
+                if self.synth_settings.enabled {
+                    // Web Audio reports its latency, so there's nothing to measure
+                    let synth = self.synth.get_or_insert_with(Synth::new);
+                    let _ = synth.resume();
+                    self.synth_latency = Some(synth.latency().round() as u32);
+                } else {
+                    self.calibration = Some(Calibration::default());
+                    ctx.link().send_message(SettingsMsg::OnCalibrationProbe);
+                }
+            }
+            SettingsMsg::OnCalibrationProbe => {
+                if let Some(calibration) = self.calibration.as_mut() {
+                    let volume = ctx.props().volume.gain(Sound::Intermediate);
+                    calibration.probe = Some(latency::probe(
+                        self.intermediate_sound,
+                        volume,
+                        ctx.link().callback(SettingsMsg::OnLatencySample),
+                    ));
+                }
+                return false;
+            }
+            SettingsMsg::OnLatencySample(sample) => {
+                let Some(calibration) = self.calibration.as_mut() else {
+                    return false;
+                };
+                match sample {
+                    Some(sample) => {
+                        if let Some(latency) = calibration.add(sample) {
+                            self.latency = Some(latency);
+                            Db::new().set_latency(latency);
+                            self.calibration = None;
+                        } else {
+                            let scope = ctx.link().clone();
+                            calibration.next = Some(Timeout::new(RUN_SPACING, move || {
+                                scope.send_message(SettingsMsg::OnCalibrationProbe);
+                            }));
+                            return false;
+                        }
+                    }
+                    None => {
+                        self.calibration = None;
+                        self.modal_title = "Calibration Failed".to_string();
+                        self.modal_text = "The test bell didn't start playing. Make sure sounds are allowed and try again.".to_string();
+                        self.modal_visible = true;
+                        self.modal_callback = ctx.link().callback(|_| SettingsMsg::CloseModal);
+                    }
+                }
+            }
+            SettingsMsg::OnImportButton => {
+                if let Some(input) = self.import_input.cast::<HtmlInputElement>() {
+                    input.click();
+                }
+            }
+            SettingsMsg::OnImportFileSelected => {
+                let input = self.import_input.cast::<HtmlInputElement>().unwrap();
+                if let Some(file) = input.files().and_then(|files| files.get(0)) {
+                    let reader = FileReader::new().unwrap();
+                    let on_load = {
+                        let scope = ctx.link().clone();
+                        let reader = reader.clone();
+                        move |_: &Event| {
+                            let content = reader.result().unwrap()
+                                .as_string()
+                                .unwrap_or_default();
+                            scope.send_message(SettingsMsg::OnImportFileLoaded(content));
+                        }
+                    };
+                    self.import_reader = Some(EventListener::once(&reader, "load", on_load));
+                    reader.read_as_text(&file).unwrap();
+                }
+                // Allow selecting the same file again
+                input.set_value("");
+            }
+            SettingsMsg::OnImportFileLoaded(content) => {
+                self.import_reader = None;
+                let preview = Db::new().preview_import(&content);
+                self.modal_title = "Import Sessions".to_string();
+                self.modal_text = format!(
+                    "New: {}, duplicate: {}, invalid: {}. Import new sessions?",
+                    preview.new_count(),
+                    preview.duplicate,
+                    preview.invalid,
+                );
+                self.modal_visible = true;
+                self.modal_callback = ctx.link().callback(SettingsMsg::OnImportConfirmed);
+                self.import_preview = Some(preview);
+            }
+            SettingsMsg::OnImportConfirmed(ok) => {
+                if let Some(preview) = self.import_preview.take() {
+                    if ok && preview.new_count() > 0 {
+                        Db::new().import_sessions(preview);
+                    }
+                }
+                ctx.link().send_message(SettingsMsg::CloseModal);
+            }
+            SettingsMsg::CloseModal => {
                 self.modal_title = String::new();
                 self.modal_text = String::new();
                 self.modal_visible = false;
@@ -187,14 +581,24 @@ impl Component for Settings {
         true
     }
 
+    fn destroy(&mut self, _ctx: &Context<Self>) {
+        // Stop listening for test plays that are still running
+        self.calibration = None;
+    }
+
     fn view(&self, ctx: &Context<Self>) -> Html {
-        let volume = match ctx.props().volume {
-            VolumeLevel::Max => 3,
-            VolumeLevel::Moderate => 2,
-            VolumeLevel::Low => 1,
-        };
+        let volume = ctx.props().volume;
+        let on_volume_change = ctx.link().callback(|volume| SettingsMsg::OnVolumeChange(volume));
 
         let back_icon_url = platform_url("assets/icons/back.svg");
+        let on_sound_change = ctx.link().callback(|_| SettingsMsg::OnBellSoundChange);
+        let synth = self.synth_settings;
+        let vibration_supported = vibration::is_supported();
+        let visual = self.visual;
+        let ambient = self.ambient;
+        let closing = self.closing;
+        let on_closing_change = ctx.link().callback(SettingsMsg::OnClosingChange);
+        let on_visual_change = ctx.link().callback(SettingsMsg::OnVisualChange);
 
         html! {
             <>
@@ -206,12 +610,258 @@ impl Component for Settings {
                 </header>
                 <main>
                     <section class="setting">
-                        <div>{ "Sound volume" }</div>
-                        <SoundRange
-                            value={volume}
-                            on_change={ctx.link().callback(|val| SettingsMsg::OnVolumeChange(val))}
+                        <div>{ format!("Sound volume: {}%", volume.master) }</div>
+                        <div class="sound-select">
+                            <SoundRange
+                                value={volume.master as usize}
+                                on_change={on_volume_change.reform(move |master| Volume { master: master as u32, ..volume })}
+                            />
+                            <button class="text-button"
+                                onclick={ctx.link().callback(|_| SettingsMsg::OnPreviewSound(Sound::Intermediate))}
+                            >
+                                { "Test" }
+                            </button>
+                        </div>
+                    </section>
+                    <section class="setting">
+                        <div>
+                            { "Signals" }
+                            if !vibration_supported {
+                                <div class="hint">{ "Vibration is not supported on this device" }</div>
+                            }
+                        </div>
+                        <select
+                            ref={self.modality_input.clone()}
+                            onchange={ctx.link().callback(|_| SettingsMsg::OnModalityChange)}
+                        >
+                        { [
+                            (SignalModality::Sound, "Sound"),
+                            (SignalModality::Vibration, "Vibration"),
+                            (SignalModality::Both, "Sound and vibration"),
+                            (SignalModality::Silent, "None (visual only)"),
+                        ]
+                            .into_iter()
+                            .map(|(modality, title)| html! {
+                                <option
+                                    value={modality.id().to_string()}
+                                    selected={modality == self.modality}
+                                    disabled={modality.has_vibration() && !vibration_supported}
+                                >
+                                    { title }
+                                </option>
+                            })
+                            .collect::<Html>()
+                        }
+                        </select>
+                    </section>
+                    <section class="setting">
+                        <div>
+                            { "Visual signals" }
+                            <div class="hint">{ "Show bells as a full-screen colour cue" }</div>
+                        </div>
+                        <Switch
+                            value={visual.enabled}
+                            on_change={on_visual_change.reform(move |enabled| VisualSignal { enabled, ..visual })}
                         />
                     </section>
+                    if visual.enabled {
+                        <section class="setting tone-setting">
+                            <div class="chart-overlays">
+                            { [(VisualStyle::Pulse, "Pulse"), (VisualStyle::Flash, "Flash")]
+                                .into_iter()
+                                .map(|(style, title)| {
+                                    let class = if style == visual.style { "tag selected" } else { "tag" };
+                                    html! {
+                                        <button {class}
+                                            onclick={on_visual_change.reform(move |_| VisualSignal { style, ..visual })}
+                                        >
+                                            { title }
+                                        </button>
+                                    }
+                                })
+                                .collect::<Html>()
+                            }
+                            </div>
+                            { gain_slider(
+                                "Intensity",
+                                visual.intensity,
+                                on_visual_change.reform(move |intensity| VisualSignal { intensity, ..visual }),
+                            ) }
+                        </section>
+                        <section class="setting">
+                            <div>
+                                { "Respect reduced motion" }
+                                <div class="hint">{ "Use a slow fade if the system asks for less motion" }</div>
+                            </div>
+                            <Switch
+                                value={visual.reduced_motion}
+                                on_change={on_visual_change.reform(move |reduced_motion| VisualSignal { reduced_motion, ..visual })}
+                            />
+                        </section>
+                    }
+                    <section class="setting tone-setting">
+                        <div class="hint">{ "Loudness of every sound relative to the volume" }</div>
+                        { gain_slider(
+                            "Opening bell",
+                            volume.opening,
+                            on_volume_change.reform(move |opening| Volume { opening, ..volume }),
+                        ) }
+                        { gain_slider(
+                            "Signal bells",
+                            volume.intermediate,
+                            on_volume_change.reform(move |intermediate| Volume { intermediate, ..volume }),
+                        ) }
+                        { gain_slider(
+                            "Final sound",
+                            volume.closing,
+                            on_volume_change.reform(move |closing| Volume { closing, ..volume }),
+                        ) }
+                    </section>
+                    <section class="setting tone-setting">
+                        <div>
+                            { "Closing sequence" }
+                            <div class="hint">{ "Final sound strikes and a silent rest before rating a session" }</div>
+                        </div>
+                        { count_slider(
+                            "Strikes",
+                            closing.strikes.to_string(),
+                            (1, 5, 1),
+                            closing.strikes,
+                            on_closing_change.reform(move |strikes| ClosingSequence { strikes, ..closing }),
+                        ) }
+                        if closing.strikes > 1 {
+                            { count_slider(
+                                "Spacing",
+                                format!("{} s", closing.spacing),
+                                (2, 15, 1),
+                                closing.spacing,
+                                on_closing_change.reform(move |spacing| ClosingSequence { spacing, ..closing }),
+                            ) }
+                        }
+                        { count_slider(
+                            "Rest",
+                            if closing.rest == 0 { "None".to_string() } else { format!("{} s", closing.rest) },
+                            (0, 120, 5),
+                            closing.rest,
+                            on_closing_change.reform(move |rest| ClosingSequence { rest, ..closing }),
+                        ) }
+                    </section>
+                    if closing.strikes > 1 {
+                        <section class="setting">
+                            <div>
+                                { "Fade strikes" }
+                                <div class="hint">{ "Strike each time a bit quieter" }</div>
+                            </div>
+                            <Switch
+                                value={closing.fade}
+                                on_change={on_closing_change.reform(move |fade| ClosingSequence { fade, ..closing })}
+                            />
+                        </section>
+                    }
+                    { self.speech_settings(ctx) }
+                    <section class="setting">
+                        <div>
+                            { "Background sound" }
+                            <div class="hint">{ "Played quietly under the bells during a session" }</div>
+                        </div>
+                        <select
+                            ref={self.ambient_input.clone()}
+                            onchange={ctx.link().callback(|_| SettingsMsg::OnAmbientSoundChange)}
+                        >
+                        { [
+                            (None, "", "None"),
+                            (Some(AmbientSound::Rain), "rain", "Rain"),
+                            (Some(AmbientSound::BrownNoise), "brown", "Brown noise"),
+                            (Some(AmbientSound::Stream), "stream", "Stream"),
+                        ]
+                            .into_iter()
+                            .map(|(sound, value, title)| html! {
+                                <option {value} selected={sound == ambient.sound}>{ title }</option>
+                            })
+                            .collect::<Html>()
+                        }
+                        </select>
+                    </section>
+                    if ambient.sound.is_some() {
+                        <section class="setting tone-setting">
+                            { gain_slider(
+                                "Background volume",
+                                ambient.volume,
+                                ctx.link().callback(move |volume| {
+                                    SettingsMsg::OnAmbientChange(AmbientSettings { volume, ..ambient })
+                                }),
+                            ) }
+                        </section>
+                    }
+                    <section class="setting">
+                        <div>
+                            { "Synthesised sounds" }
+                            <div class="hint">{ "Generate bell tones instead of playing recordings" }</div>
+                        </div>
+                        <Switch
+                            value={synth.enabled}
+                            on_change={ctx.link().callback(move |enabled| {
+                                SettingsMsg::OnSynthChange(SynthSettings { enabled, ..synth })
+                            })}
+                        />
+                    </section>
+                    if synth.enabled {
+                        { tone_controls(
+                            "Bell tone",
+                            synth.bell,
+                            (440.0, 1760.0),
+                            ctx.link().callback(move |bell| SettingsMsg::OnSynthChange(SynthSettings { bell, ..synth })),
+                            ctx.link().callback(|_| SettingsMsg::OnPreviewSound(Sound::Intermediate)),
+                        ) }
+                        { tone_controls(
+                            "Bowl tone",
+                            synth.bowl,
+                            (110.0, 440.0),
+                            ctx.link().callback(move |bowl| SettingsMsg::OnSynthChange(SynthSettings { bowl, ..synth })),
+                            ctx.link().callback(|_| SettingsMsg::OnPreviewSound(Sound::Final)),
+                        ) }
+                    } else {
+                        <section class="setting">
+                            <div>{ "Bell sound" }</div>
+                            { sound_select(
+                                INTERMEDIATE_SOUNDS,
+                                self.intermediate_sound,
+                                self.intermediate_sound_input.clone(),
+                                on_sound_change.clone(),
+                                ctx.link().callback(|_| SettingsMsg::OnPreviewSound(Sound::Intermediate)),
+                            ) }
+                        </section>
+                        <section class="setting">
+                            <div>{ "Final sound" }</div>
+                            { sound_select(
+                                FINAL_SOUNDS,
+                                self.final_sound,
+                                self.final_sound_input.clone(),
+                                on_sound_change,
+                                ctx.link().callback(|_| SettingsMsg::OnPreviewSound(Sound::Final)),
+                            ) }
+                        </section>
+                    }
+                    <section class="setting">
+                        <div>
+                            { "Sound latency" }
+                            <div class="hint">
+                            { match (self.synth_settings.enabled, self.synth_latency, self.latency) {
+                                (true, Some(latency), _) | (false, _, Some(latency)) => {
+                                    format!("{latency} ms, bells are started this much ahead")
+                                }
+                                (true, None, _) => "Reported by Web Audio".to_string(),
+                                (false, _, None) => "Not calibrated".to_string(),
+                            } }
+                            </div>
+                        </div>
+                        <button class="text-button"
+                            disabled={self.calibration.is_some()}
+                            onclick={ctx.link().callback(|_| SettingsMsg::OnCalibrate)}
+                        >
+                            { if self.calibration.is_some() { "Measuring…" } else { "Calibrate" } }
+                        </button>
+                    </section>
                     <section class="setting">
                         <div>
                             { "Do Not Disturb mode" }
@@ -222,6 +872,127 @@ impl Component for Settings {
                             on_change={ctx.link().callback(|val| SettingsMsg::OnDNDChange(val))}
                         />
                     </section>
+                    <section class="setting">
+                        <div>
+                            { "Day starts at" }
+                            <div class="hint">{ "Sessions before this time count to the previous day" }</div>
+                        </div>
+                        <select
+                            ref={self.day_start_input.clone()}
+                            onchange={ctx.link().callback(|_| SettingsMsg::OnDayStartChange)}
+                        >
+                        { (0..=12)
+                            .map(|hour| {
+                                let minutes = hour * 60;
+                                html! {
+                                    <option
+                                        value={minutes.to_string()}
+                                        selected={minutes == self.day_start}
+                                    >
+                                        { format!("{hour:02}:00") }
+                                    </option>
+                                }
+                            })
+                            .collect::<Html>()
+                        }
+                        </select>
+                    </section>
+                    <section class="setting tags-setting">
+                        <div>
+                            { "Session tags" }
+                            <div class="hint">{ "Quick picks to describe a session after rating it" }</div>
+                        </div>
+                        <div class="tags">
+                        { self.tags
+                            .iter()
+                            .map(|tag| {
+                                let tag = tag.clone();
+                                html! {
+                                    <button class="tag selected"
+                                        onclick={ctx.link().callback({
+                                            let tag = tag.clone();
+                                            move |_| SettingsMsg::OnRemoveTag(tag.clone())
+                                        })}
+                                    >
+                                        { format!("{tag} ×") }
+                                    </button>
+                                }
+                            })
+                            .collect::<Html>()
+                        }
+                        </div>
+                        <div class="tag-input">
+                            <input type="text" placeholder="New tag" ref={self.tag_input.clone()} />
+                            <button class="text-button"
+                                onclick={ctx.link().callback(|_| SettingsMsg::OnAddTag)}
+                            >
+                                { "Add" }
+                            </button>
+                        </div>
+                    </section>
+                    <section class="setting tags-setting">
+                        <div>
+                            { "Practice goals" }
+                            <div class="hint">{ "Targets to track on the main screen" }</div>
+                        </div>
+                        <div class="tags">
+                        { self.goals
+                            .iter()
+                            .enumerate()
+                            .map(|(index, goal)| html! {
+                                <button class="tag selected"
+                                    onclick={ctx.link().callback(move |_| SettingsMsg::OnRemoveGoal(index))}
+                                >
+                                    { format!("{} ×", goal.title()) }
+                                </button>
+                            })
+                            .collect::<Html>()
+                        }
+                        </div>
+                        <div class="tag-input">
+                            <input type="number"
+                                min="0"
+                                step={if self.goal_metric == GoalMetric::AvgScore { "0.5" } else { "1" }}
+                                placeholder="Target"
+                                ref={self.goal_target_input.clone()}
+                            />
+                            <select
+                                ref={self.goal_metric_input.clone()}
+                                onchange={ctx.link().callback(|_| SettingsMsg::OnGoalMetricChange)}
+                            >
+                                <option value="sessions">{ "sessions" }</option>
+                                <option value="minutes">{ "minutes" }</option>
+                                <option value="score">{ "average score" }</option>
+                            </select>
+                            <select ref={self.goal_period_input.clone()}>
+                                <option value="day">{ "per day" }</option>
+                                <option value="week">{ "per week" }</option>
+                                <option value="month">{ "per month" }</option>
+                            </select>
+                            <button class="text-button"
+                                onclick={ctx.link().callback(|_| SettingsMsg::OnAddGoal)}
+                            >
+                                { "Add" }
+                            </button>
+                        </div>
+                    </section>
+                    <section class="setting">
+                        <div>
+                            { "Import sessions" }
+                            <div class="hint">{ "Add sessions from a JSON or CSV file" }</div>
+                        </div>
+                        <button class="text-button"
+                            onclick={ctx.link().callback(|_| SettingsMsg::OnImportButton)}
+                        >
+                            { "Import" }
+                        </button>
+                        <input type="file"
+                            accept=".json,.csv"
+                            style="display: none;"
+                            ref={self.import_input.clone()}
+                            onchange={ctx.link().callback(|_| SettingsMsg::OnImportFileSelected)}
+                        />
+                    </section>
                 </main>
                 <Modal
                     title={self.modal_title.clone()}
@@ -233,3 +1004,225 @@ impl Component for Settings {
         }
     }
 }
+
+impl Settings {
+    /// Spoken announcements switch, language, voice and rate
+    fn speech_settings(&self, ctx: &Context<Self>) -> Html {
+        let speech = self.speech.clone();
+        if !speech::is_supported() {
+            return html! {
+                <section class="setting">
+                    <div>
+                        { "Spoken announcements" }
+                        <div class="hint">{ "Not supported on this device" }</div>
+                    </div>
+                </section>
+            };
+        }
+
+        let mut langs: Vec<String> = self.voices.iter().map(|v| v.lang()).collect();
+        langs.sort();
+        langs.dedup();
+        let voices: Vec<String> = self.voices
+            .iter()
+            .filter(|v| speech.lang.is_empty() || v.lang() == speech.lang)
+            .map(|v| v.name())
+            .collect();
+        let on_rate_input = {
+            let speech = speech.clone();
+            ctx.link().callback(move |e: InputEvent| {
+                let rate = e.target_unchecked_into::<HtmlInputElement>().value_as_number() as f32;
+                SettingsMsg::OnSpeechChange(SpeechSettings { rate, ..speech.clone() })
+            })
+        };
+
+        html! {
+            <>
+                <section class="setting">
+                    <div>
+                        { "Spoken announcements" }
+                        <div class="hint">{ "Announce a session start, the end of a delay, halfway and the end" }</div>
+                    </div>
+                    <Switch
+                        value={speech.enabled}
+                        on_change={{
+                            let speech = speech.clone();
+                            ctx.link().callback(move |enabled| {
+                                SettingsMsg::OnSpeechChange(SpeechSettings { enabled, ..speech.clone() })
+                            })
+                        }}
+                    />
+                </section>
+                if speech.enabled {
+                    <section class="setting tone-setting">
+                        <div class="sound-select">
+                            { "Language" }
+                            <select
+                                ref={self.speech_lang_input.clone()}
+                                onchange={ctx.link().callback(|_| SettingsMsg::OnSpeechLangChange)}
+                            >
+                                <option value="" selected={speech.lang.is_empty()}>{ "Default" }</option>
+                                { langs
+                                    .into_iter()
+                                    .map(|lang| html! {
+                                        <option value={lang.clone()} selected={lang == speech.lang}>{ lang.clone() }</option>
+                                    })
+                                    .collect::<Html>()
+                                }
+                            </select>
+                        </div>
+                        <div class="sound-select">
+                            { "Voice" }
+                            <select
+                                ref={self.speech_voice_input.clone()}
+                                onchange={ctx.link().callback(|_| SettingsMsg::OnSpeechVoiceChange)}
+                            >
+                                <option value="" selected={speech.voice.is_empty()}>{ "Default" }</option>
+                                { voices
+                                    .into_iter()
+                                    .map(|voice| html! {
+                                        <option value={voice.clone()} selected={voice == speech.voice}>{ voice.clone() }</option>
+                                    })
+                                    .collect::<Html>()
+                                }
+                            </select>
+                            <button class="text-button" onclick={ctx.link().callback(|_| SettingsMsg::OnSpeechTest)}>
+                                { "Play" }
+                            </button>
+                        </div>
+                        <label class="tone-slider">
+                            { format!("Rate: {:.1}×", speech.rate) }
+                            <input type="range"
+                                min="0.5"
+                                max="2"
+                                step="0.1"
+                                value={speech.rate.to_string()}
+                                oninput={on_rate_input}
+                            />
+                        </label>
+                    </section>
+                }
+            </>
+        }
+    }
+}
+
+/// Select of a sound from a registry with a preview button
+fn sound_select(
+    sounds: &'static [BellSound],
+    selected: &'static BellSound,
+    input: NodeRef,
+    on_change: Callback<Event>,
+    on_preview: Callback<MouseEvent>,
+) -> Html {
+    html! {
+        <div class="sound-select">
+            <select ref={input} onchange={on_change}>
+            { sounds
+                .iter()
+                .filter(|sound| sound.is_available())
+                .map(|sound| html! {
+                    <option value={sound.id} selected={sound == selected}>{ sound.title }</option>
+                })
+                .collect::<Html>()
+            }
+            </select>
+            <button class="text-button" onclick={on_preview}>{ "Play" }</button>
+        </div>
+    }
+}
+
+/// Sliders of synthesised tone parameters. `pitch_range` is in Hz.
+fn tone_controls(
+    title: &str,
+    tone: Tone,
+    pitch_range: (f32, f32),
+    on_change: Callback<Tone>,
+    on_preview: Callback<MouseEvent>,
+) -> Html {
+    let slider = |label: &str, value: f32, (min, max, step): (f32, f32, f32), update: fn(Tone, f32) -> Tone| {
+        let oninput = on_change.reform(move |e: InputEvent| {
+            let value = e.target_unchecked_into::<HtmlInputElement>().value_as_number() as f32;
+            update(tone, value)
+        });
+        html! {
+            <label class="tone-slider">
+                { label }
+                <input type="range"
+                    min={min.to_string()}
+                    max={max.to_string()}
+                    step={step.to_string()}
+                    value={value.to_string()}
+                    {oninput}
+                />
+            </label>
+        }
+    };
+
+    html! {
+        <section class="setting tone-setting">
+            <div class="sound-select">
+                { title }
+                <button class="text-button" onclick={on_preview}>{ "Play" }</button>
+            </div>
+            { slider(
+                "Pitch",
+                tone.pitch,
+                (pitch_range.0, pitch_range.1, 1.0),
+                |tone, pitch| Tone { pitch, ..tone },
+            ) }
+            { slider(
+                "Decay",
+                tone.decay,
+                (1.0, 20.0, 0.5),
+                |tone, decay| Tone { decay, ..tone },
+            ) }
+            { slider(
+                "Harmonics",
+                tone.harmonics,
+                (0.0, 0.9, 0.05),
+                |tone, harmonics| Tone { harmonics, ..tone },
+            ) }
+        </section>
+    }
+}
+
+/// Slider of a whole number shown as `display`
+fn count_slider(
+    label: &str,
+    display: String,
+    (min, max, step): (u32, u32, u32),
+    value: u32,
+    on_change: Callback<u32>,
+) -> Html {
+    let oninput = on_change.reform(|e: InputEvent| {
+        e.target_unchecked_into::<HtmlInputElement>().value().parse::<u32>().unwrap()
+    });
+
+    html! {
+        <label class="tone-slider">
+            { format!("{label}: {display}") }
+            <input type="range"
+                min={min.to_string()}
+                max={max.to_string()}
+                step={step.to_string()}
+                value={value.to_string()}
+                {oninput}
+            />
+        </label>
+    }
+}
+
+/// Slider of a gain or an intensity in percents
+fn gain_slider(label: &str, value: u32, on_change: Callback<u32>) -> Html {
+    let oninput = on_change.reform(|e: InputEvent| {
+        e.target_unchecked_into::<HtmlInputElement>().value().parse::<u32>().unwrap()
+    });
+
+    html! {
+        <label class="tone-slider">
+            { format!("{label}: {value}%") }
+            <input type="range" min="0" max="100" value={value.to_string()} {oninput} />
+        </label>
+    }
+}
@@ -0,0 +1,303 @@
+use web_sys::HtmlSelectElement;
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+use crate::analytics::{Correlations, GroupMean, Regression};
+use crate::app::{Route, platform_url};
+use crate::app::components::{
+    charts::BarChart,
+    heatmap::{Heatmap, HeatmapMetric},
+    section_title::SectionTitle,
+};
+use crate::calendar::format_day;
+use crate::db::{Db, SavedSession};
+use crate::stats::{self, Granularity, PeriodAvg, TIME_SLOT_HOURS};
+
+#[allow(clippy::enum_variant_names)]
+pub enum StatsMsg {
+    OnBackButton,
+    OnTagFilterChange,
+    OnHeatmapMetricChange(HeatmapMetric),
+    OnDaySelected(i64),
+}
+
+pub struct Stats {
+    /// App database
+    db: Db,
+    /// User defined session tags
+    tags: Vec<String>,
+    /// Tag to filter statistics by
+    tag_filter: Option<String>,
+    /// Tag filter select ref
+    tag_filter_input: NodeRef,
+    /// Value shown by the calendar heatmap
+    heatmap_metric: HeatmapMetric,
+    /// Day selected on the calendar heatmap
+    selected_day: Option<i64>,
+    /// Statistics of filtered sessions
+    stats: stats::Stats,
+    /// Daily averages shown by the calendar heatmap
+    days: Vec<PeriodAvg>,
+    /// Current day number
+    today: i64,
+    /// Sessions of the selected day
+    day_sessions: Vec<SavedSession>,
+    /// Score factors of filtered sessions
+    correlations: Correlations,
+}
+
+impl Component for Stats {
+    type Message = StatsMsg;
+    type Properties = ();
+
+    fn create(_ctx: &Context<Self>) -> Self {
+        let db = Db::new();
+        let tags = db.get_tags();
+
+        let mut stats = Self {
+            db,
+            tags,
+            tag_filter: None,
+            tag_filter_input: NodeRef::default(),
+            heatmap_metric: HeatmapMetric::default(),
+            selected_day: None,
+            stats: stats::Stats::default(),
+            days: Vec::new(),
+            today: 0,
+            day_sessions: Vec::new(),
+            correlations: Correlations::default(),
+        };
+        stats.load();
+        stats
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            StatsMsg::OnBackButton => {
+                let navigator = ctx.link().navigator().unwrap();
+                navigator.push(&Route::Home);
+            }
+            StatsMsg::OnTagFilterChange => {
+                if let Some(input) = self.tag_filter_input.cast::<HtmlSelectElement>() {
+                    let value = input.value();
+                    self.tag_filter = if value.is_empty() { None } else { Some(value) };
+                    self.load();
+                }
+            }
+            StatsMsg::OnHeatmapMetricChange(metric) => {
+                self.heatmap_metric = metric;
+            }
+            StatsMsg::OnDaySelected(day) => {
+                self.selected_day = if self.selected_day == Some(day) { None } else { Some(day) };
+                self.load_day_sessions();
+            }
+        }
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let back_icon_url = platform_url("assets/icons/back.svg");
+        let stats = &self.stats;
+
+        let score = |value: Option<f32>| match value {
+            Some(value) => format!("{value:.2}"),
+            None => "–".to_string(),
+        };
+        let best_time = match stats.best_time {
+            Some((hour, avg)) => format!(
+                "{:02}:00–{:02}:00 ({avg:.2})",
+                hour,
+                (hour + TIME_SLOT_HOURS) % 24,
+            ),
+            None => "–".to_string(),
+        };
+        let metric_toggle = |title: &str, metric: HeatmapMetric| {
+            let class = if metric == self.heatmap_metric { "tag selected" } else { "tag" };
+            html! {
+                <button {class} onclick={ctx.link().callback(move |_| StatsMsg::OnHeatmapMetricChange(metric))}>
+                    { title }
+                </button>
+            }
+        };
+        let day_sessions = &self.day_sessions;
+        let correlations = &self.correlations;
+        let factor_chart = |title: &str, name: &'static str, groups: &[GroupMean]| {
+            if groups.is_empty() {
+                return html! {};
+            }
+            let values: Vec<f32> = groups.iter().map(|g| g.mean).collect();
+            let labels: Vec<String> = groups
+                .iter()
+                .map(|g| format!("{} (n={})", g.label, g.count))
+                .collect();
+            html! {
+                <div class="factor">
+                    <h3>{ title }</h3>
+                    <BarChart {values} {labels} max={Some(5.0)} {name} />
+                </div>
+            }
+        };
+        let regression_text = |parameter: &str, regression: Option<Regression>| match regression {
+            Some(r) => format!(
+                "Score changes by {:+.2} per minute of {parameter} (n={})",
+                r.slope,
+                r.count,
+            ),
+            None => format!("Not enough data to relate score to {parameter}"),
+        };
+        let histogram: Vec<f32> = stats.histogram.iter().map(|n| *n as f32).collect();
+        let histogram_labels: Vec<String> = stats.histogram
+            .iter()
+            .enumerate()
+            .map(|(score, n)| format!("{score} ({n})"))
+            .collect();
+
+        html! {
+            <>
+                <header>
+                    <button onclick={ctx.link().callback(|_| StatsMsg::OnBackButton)}>
+                        <img src={back_icon_url} />
+                    </button>
+                    <h1>{ "Statistics" }</h1>
+                </header>
+                <main class="stats">
+                    if !self.tags.is_empty() {
+                        <section class="setting">
+                            <div>{ "Sessions" }</div>
+                            <select
+                                ref={self.tag_filter_input.clone()}
+                                onchange={ctx.link().callback(|_| StatsMsg::OnTagFilterChange)}
+                            >
+                                <option value="" selected={self.tag_filter.is_none()}>{ "All sessions" }</option>
+                                { self.tags
+                                    .iter()
+                                    .map(|tag| html! {
+                                        <option
+                                            value={tag.clone()}
+                                            selected={self.tag_filter.as_ref() == Some(tag)}
+                                        >
+                                            { tag }
+                                        </option>
+                                    })
+                                    .collect::<Html>()
+                                }
+                            </select>
+                        </section>
+                    }
+                    <section class="stats-values">
+                        <div class="value">
+                            <strong>{ "Total sessions:" }</strong>
+                            { stats.total_sessions }
+                        </div>
+                        <div class="value">
+                            <strong>{ "Total time:" }</strong>
+                            { format!("{} h {} min", stats.total_minutes / 60, stats.total_minutes % 60) }
+                        </div>
+                        <div class="value">
+                            <strong>{ "Current streak:" }</strong>
+                            { format!("{} days", stats.current_streak) }
+                        </div>
+                        <div class="value">
+                            <strong>{ "Longest streak:" }</strong>
+                            { format!("{} days", stats.longest_streak) }
+                        </div>
+                        <div class="value">
+                            <strong>{ "Mean score:" }</strong>
+                            { score(stats.mean) }
+                        </div>
+                        <div class="value">
+                            <strong>{ "Last 7 days:" }</strong>
+                            { score(stats.mean_7_days) }
+                        </div>
+                        <div class="value">
+                            <strong>{ "Last 30 days:" }</strong>
+                            { score(stats.mean_30_days) }
+                        </div>
+                        <div class="value">
+                            <strong>{ "Best time of day:" }</strong>
+                            { best_time }
+                        </div>
+                    </section>
+                    <section>
+                        <SectionTitle
+                            title={"Practice Calendar"}
+                            hint={"Tap a day to see its sessions"}
+                        />
+                        <Heatmap
+                            days={self.days.clone()}
+                            today={self.today}
+                            metric={self.heatmap_metric}
+                            on_select={ctx.link().callback(StatsMsg::OnDaySelected)}
+                        />
+                        <div class="chart-overlays">
+                            { metric_toggle("Score", HeatmapMetric::Score) }
+                            { metric_toggle("Sessions", HeatmapMetric::Count) }
+                        </div>
+                        if let Some(day) = self.selected_day {
+                            <div class="day-sessions">
+                                <h3>{ format_day(day) }</h3>
+                                if day_sessions.is_empty() {
+                                    <div>{ "No sessions" }</div>
+                                }
+                                { day_sessions
+                                    .iter()
+                                    .map(|session| {
+                                        let minutes = session.minute_of_day();
+                                        html! {
+                                            <div class="session-params">
+                                                <span>{ format!("{:02}:{:02}", minutes / 60, minutes % 60) }</span>
+                                                <span>{ format!("{} min", session.duration) }</span>
+                                                <strong>{ format!("{}/5", session.score) }</strong>
+                                            </div>
+                                        }
+                                    })
+                                    .collect::<Html>()
+                                }
+                            </div>
+                        }
+                    </section>
+                    <section>
+                        <SectionTitle
+                            title={"Score Distribution"}
+                            hint={"Number of sessions with every score"}
+                        />
+                        <BarChart values={histogram} labels={histogram_labels} name="score-distribution" />
+                    </section>
+                    <section class="factors">
+                        <SectionTitle
+                            title={"Score Factors"}
+                            hint={"Mean score of sessions grouped by their parameters; n is a number of sessions"}
+                        />
+                        <div class="hint">{ regression_text("duration", correlations.duration_regression) }</div>
+                        <div class="hint">{ regression_text("delay", correlations.delay_regression) }</div>
+                        { factor_chart("Duration, min", "factor-duration", &correlations.duration) }
+                        { factor_chart("Delay, min", "factor-delay", &correlations.delay) }
+                        { factor_chart("Time of day, h", "factor-time-of-day", &correlations.time_of_day) }
+                        { factor_chart("Day of week", "factor-weekday", &correlations.weekday) }
+                        { factor_chart("Tags", "factor-tags", &correlations.tags) }
+                    </section>
+                </main>
+            </>
+        }
+    }
+}
+
+impl Stats {
+    /// Reads statistics of sessions with the selected tag.
+    fn load(&mut self) {
+        let tag = self.tag_filter.as_deref();
+        self.stats = self.db.get_stats(tag);
+        self.days = self.db.get_period_avgs(tag, Granularity::Day);
+        self.today = self.db.get_today();
+        self.correlations = self.db.get_correlations(tag);
+        self.load_day_sessions();
+    }
+
+    /// Reads sessions of the selected day.
+    fn load_day_sessions(&mut self) {
+        self.day_sessions = match self.selected_day {
+            Some(day) => self.db.get_day_sessions(day, self.tag_filter.as_deref()),
+            None => Vec::new(),
+        };
+    }
+}
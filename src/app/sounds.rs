@@ -0,0 +1,60 @@
+use js_sys::Reflect;
+use wasm_bindgen::JsValue;
+use web_sys::HtmlMediaElement;
+
+use crate::app::is_android;
+
+/// Bell sound that can be picked in settings
+#[derive(Debug, PartialEq)]
+pub struct BellSound {
+    /// Key the selection is saved under
+    pub id: &'static str,
+    pub title: &'static str,
+    /// File name in `assets/sounds`
+    pub file: &'static str,
+    /// Playback rate; variations of a recording are played at a different pitch
+    pub rate: f64,
+}
+
+/// Sounds of signal bells, the first one is the default
+pub const INTERMEDIATE_SOUNDS: &[BellSound] = &[
+    BellSound { id: "ding", title: "Chime", file: "ding.ogg", rate: 1.0 },
+    BellSound { id: "high-chime", title: "High chime", file: "ding.ogg", rate: 1.26 },
+    BellSound { id: "low-chime", title: "Low chime", file: "ding.ogg", rate: 0.8 },
+    BellSound { id: "light-bowl", title: "Light bowl", file: "bowl.ogg", rate: 1.5 },
+];
+
+/// Sounds of a session end, the first one is the default
+pub const FINAL_SOUNDS: &[BellSound] = &[
+    BellSound { id: "bowl", title: "Singing bowl", file: "bowl.ogg", rate: 1.0 },
+    BellSound { id: "deep-bowl", title: "Deep bowl", file: "bowl.ogg", rate: 0.75 },
+    BellSound { id: "bright-bowl", title: "Bright bowl", file: "bowl.ogg", rate: 1.33 },
+    BellSound { id: "chime", title: "Chime", file: "ding.ogg", rate: 1.0 },
+];
+
+/// Finds a sound by id falling back to the default one, also when the sound
+/// isn't available on this platform.
+pub fn find_sound(sounds: &'static [BellSound], id: &str) -> &'static BellSound {
+    sounds.iter().find(|s| s.id == id && s.is_available()).unwrap_or(&sounds[0])
+}
+
+impl BellSound {
+    /// Sound file URL relative to the web app root
+    pub fn url(&self) -> String {
+        format!("assets/sounds/{}", self.file)
+    }
+
+    /// Whether the sound can be played as intended. Cordova Media changes a
+    /// playback rate on iOS only, so variations of a recording would sound
+    /// the same as the recording on Android.
+    pub fn is_available(&self) -> bool {
+        self.rate == 1.0 || !(cfg!(cordova) && is_android())
+    }
+
+    /// Prepares an `<audio>` element to play the sound at its pitch.
+    pub fn apply_rate(&self, media: &HtmlMediaElement) {
+        // Let the rate change a pitch rather than just a tempo
+        Reflect::set(media, &JsValue::from_str("preservesPitch"), &JsValue::FALSE).unwrap();
+        media.set_playback_rate(self.rate);
+    }
+}
@@ -0,0 +1,94 @@
+use gloo_events::EventListener;
+use js_sys::Reflect;
+use wasm_bindgen::JsValue;
+use web_sys::{SpeechSynthesis, SpeechSynthesisUtterance, SpeechSynthesisVoice};
+
+use crate::settings::SpeechSettings;
+
+/// Spoken cue of a session phase
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Announcement {
+    Start,
+    DelayOver,
+    Halfway,
+    Complete,
+}
+
+impl Announcement {
+    pub fn text(&self) -> &str {
+        match self {
+            Self::Start => "Session starting",
+            Self::DelayOver => "Delay over, bells begin",
+            Self::Halfway => "Halfway",
+            Self::Complete => "Session complete, rate your session",
+        }
+    }
+}
+
+/// Second of a session the halfway of its active part is announced at
+pub fn halfway(delay: usize, duration: usize) -> usize {
+    delay + duration.saturating_sub(delay) / 2
+}
+
+fn synthesis() -> Option<SpeechSynthesis> {
+    let window = web_sys::window().unwrap();
+    // Android WebView has no speech synthesis
+    if Reflect::has(&window, &JsValue::from_str("speechSynthesis")).unwrap_or(false) {
+        window.speech_synthesis().ok()
+    } else {
+        None
+    }
+}
+
+pub fn is_supported() -> bool {
+    synthesis().is_some()
+}
+
+/// Returns installed voices. A browser may load them after a page,
+/// so they come with a `voiceschanged` event of `speechSynthesis`.
+pub fn voices() -> Vec<SpeechSynthesisVoice> {
+    match synthesis() {
+        Some(synthesis) => synthesis
+            .get_voices()
+            .iter()
+            .map(SpeechSynthesisVoice::from)
+            .collect(),
+        None => Vec::new(),
+    }
+}
+
+/// Calls `callback` each time the list of voices changes.
+pub fn on_voices_changed(callback: impl Fn() + 'static) -> Option<EventListener> {
+    synthesis().map(|synthesis| EventListener::new(&synthesis, "voiceschanged", move |_| callback()))
+}
+
+/// Speaks an announcement right away interrupting a previous one.
+pub fn announce(announcement: Announcement, settings: &SpeechSettings, volume: f64) {
+    let Some(synthesis) = synthesis() else {
+        return;
+    };
+    let utterance = SpeechSynthesisUtterance::new_with_text(announcement.text()).unwrap();
+    utterance.set_rate(settings.rate);
+    utterance.set_volume(volume as f32);
+    if !settings.lang.is_empty() {
+        utterance.set_lang(&settings.lang);
+    }
+    if let Some(voice) = voices().into_iter().find(|v| v.name() == settings.voice) {
+        utterance.set_voice(Some(&voice));
+    }
+    synthesis.cancel();
+    synthesis.speak(&utterance);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn halfway_of_active_part() {
+        assert_eq!(halfway(0, 20 * 60), 10 * 60);
+        assert_eq!(halfway(3 * 60, 15 * 60), 9 * 60);
+        // A delay longer than a half of a session
+        assert_eq!(halfway(25 * 60, 30 * 60), 27 * 60 + 30);
+    }
+}
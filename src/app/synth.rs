@@ -0,0 +1,96 @@
+use js_sys::Promise;
+use web_sys::{AudioContext, GainNode};
+
+use crate::app::Sound;
+use crate::app::latency::web_audio_latency;
+use crate::settings::{SynthSettings, Tone};
+
+/// Time for a tone to reach full loudness in seconds
+const ATTACK: f64 = 0.005;
+/// Gain an exponential fade ends with (it can't reach zero)
+const SILENCE: f32 = 0.0001;
+/// Frequency ratios of bell partials (struck metal is inharmonic)
+const BELL_PARTIALS: [f32; 5] = [1.0, 2.76, 5.40, 8.93, 13.34];
+/// Frequency ratios of singing bowl partials
+const BOWL_PARTIALS: [f32; 5] = [1.0, 2.71, 5.02, 7.91, 11.36];
+/// Relative detuning of a second oscillator that makes a bowl "beat"
+const BOWL_BEAT: f32 = 0.003;
+
+impl SynthSettings {
+    pub fn tone(&self, sound: Sound) -> &Tone {
+        match sound {
+            Sound::Opening | Sound::Intermediate => &self.bell,
+            Sound::Final => &self.bowl,
+        }
+    }
+}
+
+/// Bell and bowl tones generated with the Web Audio API
+pub struct Synth {
+    context: AudioContext,
+}
+
+impl Synth {
+    pub fn new() -> Self {
+        Self {
+            context: AudioContext::new().expect("Web Audio not available"),
+        }
+    }
+
+    /// Lets a context created outside of a user gesture play.
+    /// The promise is rejected if a browser doesn't allow it.
+    pub fn resume(&self) -> Promise {
+        self.context.resume().unwrap()
+    }
+
+    /// Output latency in ms as reported by a browser
+    pub fn latency(&self) -> f64 {
+        web_audio_latency(&self.context)
+    }
+
+    /// Plays a tone as an intermediate bell or a final bowl strike.
+    pub fn play(&self, sound: Sound, tone: &Tone, volume: f64) {
+        self.play_in(sound, tone, volume, 0.0);
+    }
+
+    /// Schedules a tone `delay` seconds ahead on the audio clock.
+    /// Disconnecting the returned node cancels it.
+    pub fn play_in(&self, sound: Sound, tone: &Tone, volume: f64, delay: f64) -> GainNode {
+        let start = self.context.current_time() + delay;
+        let output = self.context.create_gain().unwrap();
+        output.gain().set_value(volume as f32);
+        output.connect_with_audio_node(&self.context.destination()).unwrap();
+
+        let (partials, detunings): (&[f32], &[f32]) = match sound {
+            Sound::Opening | Sound::Intermediate => (&BELL_PARTIALS, &[0.0]),
+            Sound::Final => (&BOWL_PARTIALS, &[0.0, BOWL_BEAT]),
+        };
+        // Keep loudness of a strike independent from the harmonics setting
+        let total: f32 = (0..partials.len()).map(|k| tone.harmonics.powi(k as i32)).sum();
+        for (k, ratio) in partials.iter().enumerate() {
+            let amplitude = tone.harmonics.powi(k as i32) / total / detunings.len() as f32;
+            // Higher partials fade out faster
+            let decay = tone.decay as f64 / (1.0 + k as f64);
+            for detuning in detunings {
+                let frequency = tone.pitch * ratio * (1.0 + detuning);
+                self.partial(&output, frequency, amplitude, start, decay);
+            }
+        }
+
+        output
+    }
+
+    fn partial(&self, output: &GainNode, frequency: f32, amplitude: f32, start: f64, decay: f64) {
+        let oscillator = self.context.create_oscillator().unwrap();
+        oscillator.frequency().set_value(frequency);
+        let envelope = self.context.create_gain().unwrap();
+        let gain = envelope.gain();
+        gain.set_value_at_time(0.0, start).unwrap();
+        gain.linear_ramp_to_value_at_time(amplitude, start + ATTACK).unwrap();
+        gain.exponential_ramp_to_value_at_time(SILENCE, start + ATTACK + decay).unwrap();
+        oscillator.connect_with_audio_node(&envelope).unwrap();
+        envelope.connect_with_audio_node(output).unwrap();
+        oscillator.start_with_when(start).unwrap();
+        oscillator.stop_with_when(start + ATTACK + decay).unwrap();
+    }
+}
@@ -0,0 +1,31 @@
+use js_sys::{Array, Reflect};
+use wasm_bindgen::JsValue;
+
+use crate::app::Sound;
+
+/// Vibration patterns (alternating vibration and pause in milliseconds)
+const OPENING_PATTERN: [u32; 3] = [150, 100, 150];
+const INTERMEDIATE_PATTERN: [u32; 1] = [300];
+const FINAL_PATTERN: [u32; 5] = [500, 200, 500, 200, 1000];
+
+/// Checks if a device can vibrate.
+pub fn is_supported() -> bool {
+    let navigator = web_sys::window().unwrap().navigator();
+    Reflect::has(&navigator, &JsValue::from_str("vibrate")).unwrap_or(false)
+}
+
+/// Vibrates with a pattern of a signal. Does nothing where vibration isn't supported.
+pub fn vibrate(sound: Sound) {
+    if !is_supported() {
+        return;
+    }
+    let pattern: &[u32] = match sound {
+        Sound::Opening => &OPENING_PATTERN,
+        Sound::Intermediate => &INTERMEDIATE_PATTERN,
+        Sound::Final => &FINAL_PATTERN,
+    };
+    let pattern: Array = pattern.iter().map(|ms| JsValue::from(*ms)).collect();
+    web_sys::window().unwrap()
+        .navigator()
+        .vibrate_with_pattern(&pattern);
+}
@@ -0,0 +1,136 @@
+const SECONDS_IN_DAY: i64 = 24 * 60 * 60;
+
+pub const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// Returns a number of a "meditation day" (days since 1970-01-01) a moment belongs to.
+///
+/// `ts` is a UNIX timestamp in seconds, `offset` is a UTC offset in minutes (east is
+/// positive) and `day_start` is a number of minutes after midnight when a new day begins.
+pub fn day_number(ts: i64, offset: i32, day_start: u32) -> i64 {
+    let local = ts + offset as i64 * 60 - day_start as i64 * 60;
+    local.div_euclid(SECONDS_IN_DAY)
+}
+
+/// Returns minutes after local midnight for a moment.
+pub fn minute_of_day(ts: i64, offset: i32) -> u32 {
+    let local = ts + offset as i64 * 60;
+    (local.rem_euclid(SECONDS_IN_DAY) / 60) as u32
+}
+
+/// Converts a day number to a (year, month, day) triple.
+pub fn civil_from_days(day: i64) -> (i64, u32, u32) {
+    // https://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = day + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let y = yoe + era * 400 + if m <= 2 { 1 } else { 0 };
+
+    (y, m, d)
+}
+
+/// Converts a (year, month, day) triple to a day number.
+pub fn days_from_civil(y: i64, m: u32, d: u32) -> i64 {
+    // https://howardhinnant.github.io/date_algorithms.html#days_from_civil
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = y.div_euclid(400);
+    let yoe = y.rem_euclid(400);
+    let m = m as i64;
+    let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + d as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+
+    era * 146097 + doe - 719468
+}
+
+/// Returns a day of week for a day number (0 is Monday).
+pub fn weekday(day: i64) -> u32 {
+    // 1970-01-01 was Thursday
+    (day + 3).rem_euclid(7) as u32
+}
+
+/// Formats a day number as `YYYY-MM-DD`.
+pub fn format_day(day: i64) -> String {
+    let (y, m, d) = civil_from_days(day);
+    format!("{y}-{m:02}-{d:02}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::MONDAY;
+
+    const HOUR: i64 = 60 * 60;
+
+    #[test]
+    fn day_number_in_utc() {
+        assert_eq!(day_number(0, 0, 0), 0);
+        assert_eq!(day_number(SECONDS_IN_DAY - 1, 0, 0), 0);
+        assert_eq!(day_number(SECONDS_IN_DAY, 0, 0), 1);
+        // Moments before the epoch belong to negative days
+        assert_eq!(day_number(-1, 0, 0), -1);
+    }
+
+    #[test]
+    fn day_number_follows_utc_offset() {
+        let ts = MONDAY * SECONDS_IN_DAY + 22 * HOUR;
+        // 22:00 UTC is already the next day in UTC+3
+        assert_eq!(day_number(ts, 3 * 60, 0), MONDAY + 1);
+        // 02:00 UTC is still the previous day in UTC-5
+        let ts = MONDAY * SECONDS_IN_DAY + 2 * HOUR;
+        assert_eq!(day_number(ts, -5 * 60, 0), MONDAY - 1);
+    }
+
+    #[test]
+    fn day_number_around_day_start() {
+        let midnight = MONDAY * SECONDS_IN_DAY;
+        // A day starting at 02:00
+        assert_eq!(day_number(midnight + 2 * HOUR - 1, 0, 120), MONDAY - 1);
+        assert_eq!(day_number(midnight + 2 * HOUR, 0, 120), MONDAY);
+        // The local clock matters, not UTC
+        assert_eq!(day_number(midnight - HOUR, 3 * 60, 120), MONDAY);
+        assert_eq!(day_number(midnight - 2 * HOUR, 3 * 60, 120), MONDAY - 1);
+    }
+
+    #[test]
+    fn minute_of_day_wraps_around_midnight() {
+        assert_eq!(minute_of_day(0, 0), 0);
+        assert_eq!(minute_of_day(90 * 60, 0), 90);
+        assert_eq!(minute_of_day(0, -60), 23 * 60);
+        assert_eq!(minute_of_day(23 * HOUR, 2 * 60), 60);
+    }
+
+    #[test]
+    fn civil_dates() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(MONDAY), (2023, 1, 2));
+        assert_eq!(civil_from_days(11016), (2000, 2, 29));
+        assert_eq!(civil_from_days(11017), (2000, 3, 1));
+        assert_eq!(civil_from_days(-25508), (1900, 3, 1));
+        assert_eq!(days_from_civil(2024, 1, 1), 19723);
+        for day in -40000..40000 {
+            let (y, m, d) = civil_from_days(day);
+            assert_eq!(days_from_civil(y, m, d), day);
+        }
+    }
+
+    #[test]
+    fn weekdays() {
+        // 1970-01-01 was Thursday
+        assert_eq!(weekday(0), 3);
+        assert_eq!(weekday(-1), 2);
+        assert_eq!(weekday(MONDAY), 0);
+        assert_eq!(weekday(MONDAY + 6), 6);
+    }
+
+    #[test]
+    fn formatted_days_are_padded() {
+        assert_eq!(format_day(MONDAY), "2023-01-02");
+        assert_eq!(format_day(19722), "2023-12-31");
+    }
+}
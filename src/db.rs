@@ -1,8 +1,20 @@
+use std::collections::BTreeMap;
+
 use js_sys::Date;
 use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsValue;
 use web_sys::Storage;
 
-use crate::app::{VolumeLevel, INITIAL_DELAY, INITIAL_DURATION};
+use crate::achievements::{self, Achievement, ACHIEVEMENTS};
+use crate::analytics::{self, Correlations};
+use crate::app::{Volume, INITIAL_DELAY, INITIAL_DURATION, MAX_DURATION};
+use crate::calendar::{day_number, format_day, minute_of_day};
+use crate::goals::{self, Goal, GoalEvent, GoalProgress};
+use crate::settings::{
+    AmbientSettings, ChartOverlays, ClosingSequence, SignalModality, SpeechSettings, SynthSettings,
+    VisualSignal,
+};
+use crate::stats::{self, Granularity, PeriodAvg, Stats};
 
 const LEGACY_STORAGE_KEYS: [&str; 7] = [
     "_config:tryout",
@@ -80,93 +92,264 @@ impl Db {
             .expect("Unable to writo to LocalStorage");
     }
 
-    pub fn add_session(&self, session: Session) {
-        let date = session.date();
-        let ts = session.ts();
+    /// Returns a number of minutes after midnight when a new "meditation day" begins.
+    pub fn get_day_start(&self) -> u32 {
+        if let Ok(Some(value)) = self.local_storage.get_item("_config:dayStart") {
+            value.parse::<u32>().unwrap_or(0)
+        } else {
+            0
+        }
+    }
+
+    /// Calibrated playback latency in ms
+    pub fn get_latency(&self) -> Option<u32> {
+        if let Ok(Some(value)) = self.local_storage.get_item("_config:latency") {
+            value.parse::<u32>().ok()
+        } else {
+            None
+        }
+    }
+
+    pub fn set_latency(&self, latency: u32) {
+        self.local_storage.set_item("_config:latency", &latency.to_string())
+            .expect("Unable to writo to LocalStorage");
+    }
+
+    /// Sets a beginning of a day and regroups saved sessions accordingly.
+    pub fn set_day_start(&self, day_start: u32) {
+        self.local_storage.set_item("_config:dayStart", &day_start.to_string())
+            .expect("Unable to writo to LocalStorage");
+        self.save_sessions(self.get_sessions());
+    }
+
+    /// Saves a session and returns goals it made reached or missed.
+    pub fn add_session(&self, session: Session) -> Vec<GoalEvent> {
+        let delay = session.delay / 60;
         // Use minutes
         let duration = session.duration / 60;
-        let score = session.score;
-        // Last session date
-        let last_session_today = if let Ok(maybe_value) = self.local_storage.get_item("_data:lastSessionDate") {
-            if let Some(mut last_date) = maybe_value {
-                let prefix = LegacyStorageValues::StringValue.prefix();
-                if last_date.contains(prefix) {
-                    last_date = last_date.strip_prefix(prefix).unwrap().to_string();
-                }
-                last_date == date
-            } else {
-                false
-            }
-        } else {
-            false
+        let saved = SavedSession {
+            date: session.date(self.get_day_start()),
+            ts: session.ts(),
+            duration,
+            score: session.score,
+            note: session.note.clone(),
+            tags: session.tags.clone(),
+            tz_offset: Some(session.tz_offset()),
+            delay: Some(delay),
+            // A stopped session may end before its delay does
+            active_duration: Some(duration.saturating_sub(delay)),
+            bell_count: Some(session.bells.len()),
+            bells: session.bells.iter().map(|t| *t as u64).collect(),
+            planned_bells: session.planned_bells.iter().map(|t| *t as u64).collect(),
+            latency: session.latency,
+            pauses: session.pauses.iter().map(|(s, e)| (*s as u64, *e as u64)).collect(),
+            completed: Some(session.completed),
+            intermediate_sound: Some(session.intermediate_sound.clone()),
+            final_sound: Some(session.final_sound.clone()),
+            volume: Some(session.volume),
+            dnd: Some(session.dnd),
+            modality: Some(session.modality.clone()),
         };
-        self.local_storage.set_item("_data:lastSessionDate", &date)
-            .expect("Unable to writo to LocalStorage");
-        // Number of sessions today
-        let mut sessions_today = 1;
-        if let Ok(maybe_value) = self.local_storage.get_item("_data:sessionsToday") {
-            let mut value = maybe_value.unwrap_or("0".to_string());
-            let prefix = LegacyStorageValues::NumberValue.prefix();
-            if value.contains(prefix) {
-                value = value.strip_prefix(prefix).unwrap().to_string();
-            }
-            // If the last session was today, add to the number. Otherwise set to 1
-            if last_session_today {
-                sessions_today = value.parse::<usize>().unwrap() + 1;
+        let mut sessions = self.get_sessions();
+        sessions.push(saved);
+        self.save_sessions(sessions);
+        // Goals
+        let mut goals = self.get_goals();
+        let events = goals::check(&mut goals, &self.get_sessions(), self.get_day_start(), self.get_today());
+        self.set_goals(&goals);
+        self.update_achievements();
+
+        events
+    }
+
+    /// Returns unlock dates (`YYYY-MM-DD`) of achievements by their ids.
+    pub fn get_achievements(&self) -> BTreeMap<String, String> {
+        if let Ok(Some(value)) = self.local_storage.get_item("_data:achievements") {
+            serde_json::from_str(&value).unwrap_or_default()
+        } else {
+            BTreeMap::new()
+        }
+    }
+
+    /// Evaluates achievements from the session history, saves new unlocks
+    /// and returns newly unlocked achievements. Unlocks are kept even if
+    /// sessions that earned them are deleted.
+    pub fn update_achievements(&self) -> Vec<&'static Achievement> {
+        let mut saved = self.get_achievements();
+        let mut unlocked = Vec::new();
+        for (id, day) in achievements::unlocks(&self.get_sessions(), self.get_day_start()) {
+            if !saved.contains_key(id) {
+                saved.insert(id.to_string(), format_day(day));
+                unlocked.extend(ACHIEVEMENTS.iter().find(|a| a.id == id));
             }
-            self.local_storage.set_item("_data:sessionsToday", &sessions_today.to_string())
+        }
+        if !unlocked.is_empty() {
+            self.local_storage.set_item("_data:achievements", &serde_json::to_string(&saved).unwrap())
                 .expect("Unable to writo to LocalStorage");
         }
-        // Sessions data
-        if let Ok(maybe_value) = self.local_storage.get_item("_data:sessions") {
-            let session = SavedSession {
-                date,
-                ts,
-                duration,
-                score,
-            };
-            let mut sessions: Vec<SavedSession> = if let Some(mut value) = maybe_value {
-                let prefix = LegacyStorageValues::ObjectValue.prefix();
-                if value.contains(prefix) {
-                    value = value.strip_prefix(prefix).unwrap().to_string();
-                }
-                serde_json::from_str(&value).unwrap()
-            } else {
-                Vec::new()
-            };
-            sessions.push(session);
-            self.local_storage.set_item(
-                "_data:sessions",
-                &serde_json::to_string(&sessions).unwrap(),
-            ).expect("Unable to writo to LocalStorage");
-        }
-        // Averages date
-        if let Ok(maybe_value) = self.local_storage.get_item("_data:avgs") {
-            let mut avgs: Vec<f32> = if let Some(mut value) = maybe_value {
-                let prefix = LegacyStorageValues::ObjectValue.prefix();
-                if value.contains(prefix) {
-                    value = value.strip_prefix(prefix).unwrap().to_string();
-                }
-                serde_json::from_str(&value).unwrap()
-            } else {
-                Vec::new()
-            };
-            let new_value = if last_session_today {
-                let last_value = avgs.pop().unwrap();
-                (last_value * (sessions_today as f32 - 1.0) + score as f32) / sessions_today as f32
-            } else {
-                score as f32
-            };
-            avgs.push(new_value);
-            self.local_storage.set_item(
-                "_data:avgs",
-                &serde_json::to_string(&avgs).unwrap(),
-            ).expect("Unable to writo to LocalStorage");
+
+        unlocked
+    }
+
+    pub fn get_goals(&self) -> Vec<Goal> {
+        if let Ok(Some(value)) = self.local_storage.get_item("_config:goals") {
+            serde_json::from_str(&value).unwrap_or_default()
+        } else {
+            Vec::new()
         }
     }
 
-    pub fn get_avgs(&self) -> Vec<f32> {
-        if let Ok(maybe_value) = self.local_storage.get_item("_data:avgs") {
+    pub fn set_goals(&self, goals: &[Goal]) {
+        self.local_storage.set_item("_config:goals", &serde_json::to_string(goals).unwrap())
+            .expect("Unable to writo to LocalStorage");
+    }
+
+    /// Returns goals with their progress over current periods.
+    pub fn get_goals_progress(&self) -> Vec<(Goal, GoalProgress)> {
+        let sessions = self.get_sessions();
+        let day_start = self.get_day_start();
+        let today = self.get_today();
+        self.get_goals()
+            .into_iter()
+            .map(|goal| {
+                let progress = goal.progress(&sessions, day_start, goal.period.period_start(today));
+                (goal, progress)
+            })
+            .collect()
+    }
+
+    /// Returns average scores over periods of all sessions or sessions marked with a tag.
+    pub fn get_period_avgs(&self, tag: Option<&str>, granularity: Granularity) -> Vec<PeriodAvg> {
+        stats::period_avgs(&self.get_tagged_sessions(tag), self.get_day_start(), granularity)
+    }
+
+    pub fn get_chart_granularity(&self) -> Granularity {
+        if let Ok(Some(value)) = self.local_storage.get_item("_config:chartGranularity") {
+            serde_json::from_str(&value).unwrap_or_default()
+        } else {
+            Granularity::default()
+        }
+    }
+
+    pub fn set_chart_granularity(&self, granularity: Granularity) {
+        self.local_storage.set_item("_config:chartGranularity", &serde_json::to_string(&granularity).unwrap())
+            .expect("Unable to writo to LocalStorage");
+    }
+
+    /// Returns statistics of all sessions or sessions marked with a tag.
+    pub fn get_stats(&self, tag: Option<&str>) -> Stats {
+        stats::compute(&self.get_tagged_sessions(tag), self.get_day_start(), self.get_today())
+    }
+
+    /// Returns score against session parameters of all sessions or sessions marked with a tag.
+    pub fn get_correlations(&self, tag: Option<&str>) -> Correlations {
+        analytics::correlations(&self.get_tagged_sessions(tag), self.get_day_start())
+    }
+
+    /// Returns a day number of the current "meditation day".
+    pub fn get_today(&self) -> i64 {
+        let now = Date::new_0();
+        let ts = (now.get_time() / 1000.0) as i64;
+        day_number(ts, -now.get_timezone_offset() as i32, self.get_day_start())
+    }
+
+    /// Returns sessions of a "meditation day" marked with a tag (or all if no tag given).
+    pub fn get_day_sessions(&self, day: i64, tag: Option<&str>) -> Vec<SavedSession> {
+        let day_start = self.get_day_start();
+        let mut sessions = self.get_tagged_sessions(tag);
+        sessions.retain(|s| s.day(day_start) == day);
+
+        sessions
+    }
+
+    pub fn get_chart_overlays(&self) -> ChartOverlays {
+        if let Ok(Some(value)) = self.local_storage.get_item("_config:chartOverlays") {
+            serde_json::from_str(&value).unwrap_or_default()
+        } else {
+            ChartOverlays::default()
+        }
+    }
+
+    pub fn set_chart_overlays(&self, overlays: &ChartOverlays) {
+        self.local_storage.set_item("_config:chartOverlays", &serde_json::to_string(overlays).unwrap())
+            .expect("Unable to writo to LocalStorage");
+    }
+
+    /// Returns user defined session tags.
+    pub fn get_tags(&self) -> Vec<String> {
+        if let Ok(Some(value)) = self.local_storage.get_item("_config:tags") {
+            serde_json::from_str(&value).unwrap_or_default()
+        } else {
+            Vec::new()
+        }
+    }
+
+    pub fn set_tags(&self, tags: &[String]) {
+        self.local_storage.set_item("_config:tags", &serde_json::to_string(tags).unwrap())
+            .expect("Unable to writo to LocalStorage");
+    }
+
+    /// Parses sessions exported by the app (JSON or CSV) and compares them
+    /// against the sessions already in the database.
+    pub fn preview_import(&self, content: &str) -> ImportPreview {
+        let (records, invalid) = parse_import(content);
+        let known: Vec<String> = self.get_sessions()
+            .into_iter()
+            .map(|s| s.ts)
+            .collect();
+
+        ImportPreview::new(records, invalid, known)
+    }
+
+    /// Merges previewed sessions into the database and recomputes averages.
+    pub fn import_sessions(&self, preview: ImportPreview) {
+        let mut sessions = self.get_sessions();
+        sessions.extend(preview.sessions.into_iter().map(|mut session| {
+            // Group imported sessions by the same days as `get_sessions` does
+            session.fill_tz_offset();
+            session
+        }));
+        self.save_sessions(sessions);
+        self.update_achievements();
+    }
+
+    /// Updates score and note of a session with a given timestamp.
+    pub fn update_session(&self, ts: &str, score: usize, note: String, tags: Vec<String>) {
+        let mut sessions = self.get_sessions();
+        if let Some(session) = sessions.iter_mut().find(|s| s.ts == ts) {
+            session.score = score;
+            session.note = note;
+            session.tags = tags;
+            self.save_sessions(sessions);
+            self.update_achievements();
+        }
+    }
+
+    /// Removes a session with a given timestamp and returns it (to allow undo).
+    pub fn delete_session(&self, ts: &str) -> Option<SavedSession> {
+        let mut sessions = self.get_sessions();
+        let index = sessions.iter().position(|s| s.ts == ts)?;
+        let session = sessions.remove(index);
+        self.save_sessions(sessions);
+
+        Some(session)
+    }
+
+    /// Puts a previously deleted session back.
+    pub fn restore_session(&self, session: SavedSession) {
+        let mut sessions = self.get_sessions();
+        if sessions.iter().any(|s| s.ts == session.ts) {
+            return;
+        }
+        sessions.push(session);
+        self.save_sessions(sessions);
+        self.update_achievements();
+    }
+
+    /// Returns saved sessions. Sessions saved without a UTC offset get the offset
+    /// the device has (or had) at that moment.
+    pub fn get_sessions(&self) -> Vec<SavedSession> {
+        let mut sessions: Vec<SavedSession> = if let Ok(maybe_value) = self.local_storage.get_item("_data:sessions") {
             if let Some(mut value) = maybe_value {
                 let prefix = LegacyStorageValues::ObjectValue.prefix();
                 if value.contains(prefix) {
@@ -178,38 +361,151 @@ impl Db {
             }
         } else {
             Vec::new()
+        };
+        for session in sessions.iter_mut() {
+            session.fill_tz_offset();
         }
+
+        sessions
     }
 
-    pub fn get_sound_volume(&self) -> VolumeLevel {
-        if let Ok(value) = self.local_storage.get_item("_config:soundVolume") {
-            if let Some(mut value) = value {
-                let prefix = LegacyStorageValues::NumberValue.prefix();
-                if value.contains(prefix) {
-                    value = value.strip_prefix(prefix).unwrap().to_string();
-                    let value = match value.parse::<usize>().unwrap() {
-                        5 => 2,
-                        4 => 1,
-                        3 => 1,
-                        2 => 0,
-                        1 => 0,
-                        _ => unimplemented!(),
-                    };
-                    VolumeLevel::from_config_value(value)
-                } else {
-                    VolumeLevel::from_config_value(value.parse::<usize>().unwrap())
-                }
-            } else {
-                VolumeLevel::default()
-            }
+    /// Returns sessions marked with a tag (or all sessions if no tag given).
+    pub fn get_tagged_sessions(&self, tag: Option<&str>) -> Vec<SavedSession> {
+        let mut sessions = self.get_sessions();
+        if let Some(tag) = tag {
+            sessions.retain(|s| s.tags.iter().any(|t| t == tag));
+        }
+
+        sessions
+    }
+
+    /// Saves sessions regrouping them by day.
+    fn save_sessions(&self, mut sessions: Vec<SavedSession>) {
+        let day_start = self.get_day_start();
+        for session in sessions.iter_mut() {
+            session.date = format_day(session.day(day_start));
+        }
+        sessions.sort_by_key(|s| s.ts.parse::<u64>().unwrap_or(0));
+        self.local_storage.set_item(
+            "_data:sessions",
+            &serde_json::to_string(&sessions).unwrap(),
+        ).expect("Unable to writo to LocalStorage");
+    }
+
+    pub fn get_sound_volume(&self) -> Volume {
+        match self.local_storage.get_item("_config:volume") {
+            Ok(Some(value)) => serde_json::from_str(&value).unwrap_or_default(),
+            _ => Volume::default(),
+        }
+    }
+
+    /// Replaces a volume level of old versions with volume settings.
+    /// A level that can't be read gives the default volume.
+    pub fn migrate_sound_volume(&self) {
+        let Ok(Some(value)) = self.local_storage.get_item("_config:soundVolume") else {
+            return;
+        };
+        if let Ok(None) = self.local_storage.get_item("_config:volume") {
+            let volume = legacy_volume_level(&value).map_or_else(Volume::default, Volume::from_level);
+            self.set_sound_volume(&volume);
+        }
+        self.local_storage.remove_item("_config:soundVolume").unwrap();
+    }
+
+    pub fn set_sound_volume(&self, volume: &Volume) {
+        self.local_storage.set_item("_config:volume", &serde_json::to_string(volume).unwrap())
+            .expect("Unable to writo to LocalStorage");
+    }
+
+    /// Returns ids of selected intermediate and final sounds (empty if not selected).
+    pub fn get_bell_sounds(&self) -> (String, String) {
+        let get = |key: &str| self.local_storage.get_item(key).ok().flatten().unwrap_or_default();
+
+        (get("_config:intermediateSound"), get("_config:finalSound"))
+    }
+
+    pub fn set_bell_sounds(&self, intermediate_id: &str, final_id: &str) {
+        self.local_storage.set_item("_config:intermediateSound", intermediate_id)
+            .expect("Unable to writo to LocalStorage");
+        self.local_storage.set_item("_config:finalSound", final_id)
+            .expect("Unable to writo to LocalStorage");
+    }
+
+    pub fn get_signal_modality(&self) -> SignalModality {
+        if let Ok(Some(value)) = self.local_storage.get_item("_config:signalModality") {
+            serde_json::from_str(&value).unwrap_or_default()
+        } else {
+            SignalModality::default()
+        }
+    }
+
+    pub fn set_signal_modality(&self, modality: SignalModality) {
+        self.local_storage.set_item("_config:signalModality", &serde_json::to_string(&modality).unwrap())
+            .expect("Unable to writo to LocalStorage");
+    }
+
+    pub fn get_visual_signal(&self) -> VisualSignal {
+        if let Ok(Some(value)) = self.local_storage.get_item("_config:visualSignal") {
+            serde_json::from_str(&value).unwrap_or_default()
+        } else {
+            VisualSignal::default()
+        }
+    }
+
+    pub fn set_visual_signal(&self, visual: &VisualSignal) {
+        self.local_storage.set_item("_config:visualSignal", &serde_json::to_string(visual).unwrap())
+            .expect("Unable to writo to LocalStorage");
+    }
+
+    pub fn get_ambient_settings(&self) -> AmbientSettings {
+        if let Ok(Some(value)) = self.local_storage.get_item("_config:ambient") {
+            serde_json::from_str(&value).unwrap_or_default()
         } else {
-            VolumeLevel::default()
+            AmbientSettings::default()
         }
     }
 
-    pub fn set_sound_volume(&self, volume: &VolumeLevel) {
-        let value = volume.config_value();
-        self.local_storage.set_item("_config:soundVolume", &value.to_string())
+    pub fn set_ambient_settings(&self, ambient: &AmbientSettings) {
+        self.local_storage.set_item("_config:ambient", &serde_json::to_string(ambient).unwrap())
+            .expect("Unable to writo to LocalStorage");
+    }
+
+    pub fn get_closing_sequence(&self) -> ClosingSequence {
+        if let Ok(Some(value)) = self.local_storage.get_item("_config:closing") {
+            serde_json::from_str(&value).unwrap_or_default()
+        } else {
+            ClosingSequence::default()
+        }
+    }
+
+    pub fn set_closing_sequence(&self, closing: &ClosingSequence) {
+        self.local_storage.set_item("_config:closing", &serde_json::to_string(closing).unwrap())
+            .expect("Unable to writo to LocalStorage");
+    }
+
+    pub fn get_speech_settings(&self) -> SpeechSettings {
+        if let Ok(Some(value)) = self.local_storage.get_item("_config:speech") {
+            serde_json::from_str(&value).unwrap_or_default()
+        } else {
+            SpeechSettings::default()
+        }
+    }
+
+    pub fn set_speech_settings(&self, speech: &SpeechSettings) {
+        self.local_storage.set_item("_config:speech", &serde_json::to_string(speech).unwrap())
+            .expect("Unable to writo to LocalStorage");
+    }
+
+    pub fn get_synth_settings(&self) -> SynthSettings {
+        if let Ok(Some(value)) = self.local_storage.get_item("_config:synth") {
+            serde_json::from_str(&value).unwrap_or_default()
+        } else {
+            SynthSettings::default()
+        }
+    }
+
+    pub fn set_synth_settings(&self, synth: &SynthSettings) {
+        self.local_storage.set_item("_config:synth", &serde_json::to_string(synth).unwrap())
             .expect("Unable to writo to LocalStorage");
     }
 
@@ -246,15 +542,66 @@ impl Db {
 pub struct Session {
     date: Date,
     pub duration: usize,
+    pub delay: usize,
     pub score: usize,
+    pub note: String,
+    pub tags: Vec<String>,
+    /// Times (ms since epoch) when signal bells actually rang
+    pub bells: Vec<f64>,
+    /// Times (ms since epoch) when signal bells were planned to ring
+    pub planned_bells: Vec<f64>,
+    /// Playback latency in ms bells were started ahead by
+    pub latency: Option<u32>,
+    /// Pause intervals (ms since epoch)
+    pub pauses: Vec<(f64, f64)>,
+    /// Whether a session ran until the last bell
+    pub completed: bool,
+    pub intermediate_sound: String,
+    pub final_sound: String,
+    pub volume: f64,
+    pub dnd: bool,
+    /// Signal modality id
+    pub modality: String,
 }
 
 impl Session {
-    pub fn new(date: Date, duration: usize, score: usize) -> Self {
+    pub fn new(date: Date, duration: usize, delay: usize) -> Self {
         Self {
             date,
             duration,
-            score,
+            delay,
+            score: 0,
+            note: String::new(),
+            tags: Vec::new(),
+            bells: Vec::new(),
+            planned_bells: Vec::new(),
+            latency: None,
+            pauses: Vec::new(),
+            completed: false,
+            intermediate_sound: String::new(),
+            final_sound: String::new(),
+            volume: 0.0,
+            dnd: false,
+            modality: String::new(),
+        }
+    }
+
+    /// Registers a signal bell with its planned and actual times.
+    pub fn ring(&mut self, planned: f64, actual: f64) {
+        self.planned_bells.push(planned);
+        self.bells.push(actual);
+    }
+
+    /// Registers a beginning of a pause.
+    pub fn pause(&mut self) {
+        let now = Date::now();
+        self.pauses.push((now, now));
+    }
+
+    /// Registers an end of a pause.
+    pub fn resume(&mut self) {
+        if let Some(pause) = self.pauses.last_mut() {
+            pause.1 = Date::now();
         }
     }
 
@@ -262,30 +609,217 @@ impl Session {
         ((self.date.get_time() / 1000.0) as usize).to_string()
     }
 
-    pub fn date(&self) -> String {
-        format!(
-            "{}-{:02}-{:02}",
-            self.date.get_full_year(),
-            self.date.get_month() + 1,
-            self.date.get_date(),
-        )
+    /// UTC offset in minutes (east is positive)
+    pub fn tz_offset(&self) -> i32 {
+        -self.date.get_timezone_offset() as i32
+    }
+
+    /// Date of a "meditation day" that begins `day_start` minutes after midnight.
+    pub fn date(&self, day_start: u32) -> String {
+        let ts = (self.date.get_time() / 1000.0) as i64;
+        format_day(day_number(ts, self.tz_offset(), day_start))
     }
 }
 
 /// Session representation in a database
-#[derive(Deserialize, Serialize, Debug)]
-struct SavedSession {
-    date: String,
-    ts: String,
-    duration: usize,
-    score: usize,
+///
+/// Fields other than `date`, `ts`, `duration` and `score` were added later
+/// and are absent in older records.
+#[derive(Clone, Default, Deserialize, Serialize, Debug, PartialEq)]
+pub struct SavedSession {
+    pub date: String,
+    pub ts: String,
+    pub duration: usize,
+    pub score: usize,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub note: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// UTC offset in minutes at session start
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tz_offset: Option<i32>,
+    /// Active session delay in minutes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delay: Option<usize>,
+    /// Active session duration in minutes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub active_duration: Option<usize>,
+    /// Number of signal bells rung
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bell_count: Option<usize>,
+    /// Times (ms since epoch) when signal bells actually rang
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub bells: Vec<u64>,
+    /// Times (ms since epoch) when signal bells were planned to ring
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub planned_bells: Vec<u64>,
+    /// Playback latency in ms bells were started ahead by
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latency: Option<u32>,
+    /// Pause intervals (ms since epoch)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pauses: Vec<(u64, u64)>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completed: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub intermediate_sound: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub final_sound: Option<String>,
+    /// Sound volume (0.0 to 1.0)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub volume: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dnd: Option<bool>,
+    /// Whether signals were given by sound, vibration or both
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub modality: Option<String>,
+}
+
+impl SavedSession {
+    /// Session start time in milliseconds (as used by JS Date)
+    pub fn time_ms(&self) -> f64 {
+        self.ts.parse::<f64>().unwrap_or(0.0) * 1000.0
+    }
+
+    /// Sets the UTC offset the device has (or had) at session start if it's unknown.
+    fn fill_tz_offset(&mut self) {
+        if self.tz_offset.is_none() {
+            let date = Date::new(&JsValue::from_f64(self.time_ms()));
+            self.tz_offset = Some(-date.get_timezone_offset() as i32);
+        }
+    }
+
+    /// Day number of a "meditation day" the session belongs to.
+    pub fn day(&self, day_start: u32) -> i64 {
+        let ts = self.ts.parse::<i64>().unwrap_or(0);
+        day_number(ts, self.tz_offset.unwrap_or(0), day_start)
+    }
+
+    /// Session start time as minutes after local midnight.
+    pub fn minute_of_day(&self) -> u32 {
+        let ts = self.ts.parse::<i64>().unwrap_or(0);
+        minute_of_day(ts, self.tz_offset.unwrap_or(0))
+    }
+
+    /// Checks that a record could have been produced by the app.
+    fn is_valid(&self) -> bool {
+        let date_ok = {
+            let parts: Vec<&str> = self.date.split('-').collect();
+            parts.len() == 3
+                && parts[0].len() == 4
+                && parts.iter().all(|p| !p.is_empty() && p.chars().all(|c| c.is_ascii_digit()))
+                && (1..=12).contains(&parts[1].parse::<usize>().unwrap_or(0))
+                && (1..=31).contains(&parts[2].parse::<usize>().unwrap_or(0))
+        };
+        let ts_ok = self.ts.parse::<u64>().is_ok_and(|ts| ts > 0);
+
+        date_ok
+            && ts_ok
+            && (1..=MAX_DURATION / 60).contains(&self.duration)
+            && self.score <= 5
+    }
+}
+
+/// Result of parsing an import file, shown to a user before merging.
+pub struct ImportPreview {
+    sessions: Vec<SavedSession>,
+    pub duplicate: usize,
+    pub invalid: usize,
+}
+
+impl ImportPreview {
+    /// Sorts parsed records into new, duplicate (by timestamp) and invalid ones.
+    /// `known` are timestamps of sessions in the database.
+    fn new(records: Vec<SavedSession>, mut invalid: usize, mut known: Vec<String>) -> Self {
+        let mut sessions = Vec::new();
+        let mut duplicate = 0;
+        for record in records {
+            if !record.is_valid() {
+                invalid += 1;
+            } else if known.contains(&record.ts) {
+                duplicate += 1;
+            } else {
+                known.push(record.ts.clone());
+                sessions.push(record);
+            }
+        }
+
+        Self { sessions, duplicate, invalid }
+    }
+
+    /// Number of sessions that are not in the database yet
+    pub fn new_count(&self) -> usize {
+        self.sessions.len()
+    }
+}
+
+/// Parses JSON (array of sessions) or CSV (`date,ts,duration,score`) content.
+/// Returns parsed records and a number of records that couldn't be parsed.
+fn parse_import(content: &str) -> (Vec<SavedSession>, usize) {
+    let mut records = Vec::new();
+    let mut invalid = 0;
+    let content = content.trim();
+    if content.starts_with('[') {
+        let values: Vec<serde_json::Value> = match serde_json::from_str(content) {
+            Ok(values) => values,
+            Err(_) => return (records, 1),
+        };
+        for value in values {
+            match serde_json::from_value::<SavedSession>(value) {
+                Ok(record) => records.push(record),
+                Err(_) => invalid += 1,
+            }
+        }
+    } else {
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with("date") {
+                // Skip empty lines and a header
+                continue;
+            }
+            let fields: Vec<&str> = line.split(',').map(|f| f.trim().trim_matches('"')).collect();
+            let record = if fields.len() == 4 {
+                match (fields[2].parse::<usize>(), fields[3].parse::<usize>()) {
+                    (Ok(duration), Ok(score)) => Some(SavedSession {
+                        date: fields[0].to_string(),
+                        ts: fields[1].to_string(),
+                        duration,
+                        score,
+                        ..Default::default()
+                    }),
+                    _ => None,
+                }
+            } else {
+                None
+            };
+            match record {
+                Some(record) => records.push(record),
+                None => invalid += 1,
+            }
+        }
+    }
+
+    (records, invalid)
+}
+
+/// Parses a volume level (0 is low, 2 is max) stored by old versions.
+/// The oldest ones stored levels from 1 to 5.
+fn legacy_volume_level(value: &str) -> Option<usize> {
+    match value.strip_prefix(LegacyStorageValues::NumberValue.prefix()) {
+        Some(value) => match value.parse::<usize>() {
+            Ok(5) => Some(2),
+            Ok(3 | 4) => Some(1),
+            Ok(1 | 2) => Some(0),
+            _ => None,
+        },
+        None => value.parse::<usize>().ok().filter(|level| *level <= 2),
+    }
 }
 
 enum LegacyStorageValues {
     BoolValue,
     NumberValue,
     ObjectValue,
-    StringValue,
 }
 
 impl LegacyStorageValues {
@@ -293,8 +827,124 @@ impl LegacyStorageValues {
         match self {
             LegacyStorageValues::BoolValue => "__q_bool|",
             LegacyStorageValues::NumberValue => "__q_numb|",
-            LegacyStorageValues::StringValue => "__q_strn|",
             LegacyStorageValues::ObjectValue => "__q_objt|",
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_empty() {
+        let (records, invalid) = parse_import("");
+        assert!(records.is_empty());
+        assert_eq!(invalid, 0);
+        let (records, invalid) = parse_import("[]");
+        assert!(records.is_empty());
+        assert_eq!(invalid, 0);
+    }
+
+    #[test]
+    fn parse_json() {
+        let content = r#"[
+            {"date": "2023-01-02", "ts": "1672646400", "duration": 15, "score": 4, "tz_offset": 180},
+            {"date": "2023-01-03", "ts": "1672732800", "duration": 20, "score": 3, "tags": ["walk"]},
+            {"date": "2023-01-04"}
+        ]"#;
+        let (records, invalid) = parse_import(content);
+        assert_eq!(invalid, 1);
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].tz_offset, Some(180));
+        assert_eq!(records[1].tags, vec!["walk".to_string()]);
+    }
+
+    #[test]
+    fn parse_broken_json() {
+        let (records, invalid) = parse_import("[{\"date\": ");
+        assert!(records.is_empty());
+        assert_eq!(invalid, 1);
+    }
+
+    #[test]
+    fn parse_csv() {
+        let content = "date,ts,duration,score\n\
+            2023-01-02,1672646400,15,4\n\
+            \n\
+            \"2023-01-03\",\"1672732800\",20,3\n\
+            2023-01-04,1672819200,twenty,3\n\
+            2023-01-05,1672905600,15\n";
+        let (records, invalid) = parse_import(content);
+        assert_eq!(invalid, 2);
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].date, "2023-01-02");
+        assert_eq!(records[0].duration, 15);
+        assert_eq!(records[0].score, 4);
+        assert_eq!(records[0].tz_offset, None);
+        assert_eq!(records[1].ts, "1672732800");
+    }
+
+    #[test]
+    fn legacy_volume_levels() {
+        assert_eq!(legacy_volume_level("__q_numb|5"), Some(2));
+        assert_eq!(legacy_volume_level("__q_numb|3"), Some(1));
+        assert_eq!(legacy_volume_level("__q_numb|1"), Some(0));
+        assert_eq!(legacy_volume_level("1"), Some(1));
+        assert_eq!(legacy_volume_level("__q_numb|0"), None);
+        assert_eq!(legacy_volume_level("__q_numb|loud"), None);
+        assert_eq!(legacy_volume_level("7"), None);
+        assert_eq!(legacy_volume_level(""), None);
+    }
+
+    fn record(date: &str, ts: &str, duration: usize, score: usize) -> SavedSession {
+        SavedSession {
+            date: date.to_string(),
+            ts: ts.to_string(),
+            duration,
+            score,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn validation() {
+        assert!(record("2023-01-02", "1672646400", 15, 4).is_valid());
+        assert!(record("2023-01-02", "1672646400", 1, 0).is_valid());
+        assert!(record("2023-01-02", "1672646400", MAX_DURATION / 60, 5).is_valid());
+        assert!(!record("2023-01-02", "1672646400", 0, 4).is_valid());
+        assert!(!record("2023-01-02", "1672646400", MAX_DURATION / 60 + 1, 4).is_valid());
+        assert!(!record("2023-01-02", "1672646400", 15, 6).is_valid());
+        assert!(!record("2023-01-02", "0", 15, 4).is_valid());
+        assert!(!record("2023-01-02", "-5", 15, 4).is_valid());
+        assert!(!record("2023-13-02", "1672646400", 15, 4).is_valid());
+        assert!(!record("2023-01-32", "1672646400", 15, 4).is_valid());
+        assert!(!record("23-01-02", "1672646400", 15, 4).is_valid());
+        assert!(!record("2023-01", "1672646400", 15, 4).is_valid());
+    }
+
+    #[test]
+    fn duplicates_are_found_by_timestamp() {
+        let records = vec![
+            record("2023-01-02", "1672646400", 15, 4),
+            // Already in the database
+            record("2023-01-03", "1672732800", 15, 4),
+            // Repeated within the file
+            record("2023-01-02", "1672646400", 20, 2),
+            record("2023-01-04", "1672819200", 15, 9),
+        ];
+        let preview = ImportPreview::new(records, 1, vec!["1672732800".to_string()]);
+        assert_eq!(preview.new_count(), 1);
+        assert_eq!(preview.sessions[0].score, 4);
+        assert_eq!(preview.duplicate, 2);
+        assert_eq!(preview.invalid, 2);
+    }
+
+    #[test]
+    fn nothing_to_import() {
+        let preview = ImportPreview::new(Vec::new(), 0, Vec::new());
+        assert_eq!(preview.new_count(), 0);
+        assert_eq!(preview.duplicate, 0);
+        assert_eq!(preview.invalid, 0);
+    }
+}
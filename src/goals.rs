@@ -0,0 +1,243 @@
+use serde::{Deserialize, Serialize};
+
+use crate::db::SavedSession;
+use crate::stats::Granularity;
+
+/// Figure a goal sets a target for
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub enum GoalMetric {
+    Sessions,
+    Minutes,
+    AvgScore,
+}
+
+/// Practice goal like "5 sessions per week"
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Goal {
+    pub metric: GoalMetric,
+    pub target: f32,
+    pub period: Granularity,
+    /// First day of the last period the goal was checked in
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checked_period: Option<i64>,
+    /// First day of the last period the goal was reached in
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reached_period: Option<i64>,
+}
+
+/// Progress of a goal over a period
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GoalProgress {
+    pub value: f32,
+    /// Part of a target done, from 0 to 1
+    pub fraction: f32,
+    pub reached: bool,
+}
+
+/// Goal reached or missed after a session was added
+#[derive(Clone, Debug, PartialEq)]
+pub struct GoalEvent {
+    pub goal: Goal,
+    pub progress: GoalProgress,
+    pub reached: bool,
+}
+
+impl Goal {
+    pub fn new(metric: GoalMetric, target: f32, period: Granularity) -> Self {
+        Self {
+            metric,
+            target,
+            period,
+            checked_period: None,
+            reached_period: None,
+        }
+    }
+
+    pub fn title(&self) -> String {
+        let period = match self.period {
+            Granularity::Day => "day",
+            Granularity::Week => "week",
+            Granularity::Month => "month",
+        };
+        match self.metric {
+            GoalMetric::Sessions => format!("{} sessions per {period}", self.target),
+            GoalMetric::Minutes => format!("{} minutes per {period}", self.target),
+            GoalMetric::AvgScore => format!("Average score ≥ {:.1} per {period}", self.target),
+        }
+    }
+
+    /// Formats a progress value in units of a goal.
+    pub fn format_value(&self, value: f32) -> String {
+        match self.metric {
+            GoalMetric::Sessions => format!("{value}"),
+            GoalMetric::Minutes => format!("{value} min"),
+            GoalMetric::AvgScore => format!("{value:.1}"),
+        }
+    }
+
+    /// Session counts and minutes only grow within a period, so such goals
+    /// are reached as soon as a target is met. An average score can drop,
+    /// so it's judged when a period is over.
+    fn is_cumulative(&self) -> bool {
+        self.metric != GoalMetric::AvgScore
+    }
+
+    /// Computes progress over a period starting with day `period`.
+    pub fn progress(&self, sessions: &[SavedSession], day_start: u32, period: i64) -> GoalProgress {
+        let end = self.period.next_period_start(period);
+        let sessions: Vec<&SavedSession> = sessions
+            .iter()
+            .filter(|s| (period..end).contains(&s.day(day_start)))
+            .collect();
+        let value = match self.metric {
+            GoalMetric::Sessions => sessions.len() as f32,
+            GoalMetric::Minutes => sessions.iter().map(|s| s.duration).sum::<usize>() as f32,
+            GoalMetric::AvgScore => {
+                if sessions.is_empty() {
+                    0.0
+                } else {
+                    sessions.iter().map(|s| s.score).sum::<usize>() as f32 / sessions.len() as f32
+                }
+            }
+        };
+        let fraction = if self.target > 0.0 { (value / self.target).min(1.0) } else { 1.0 };
+
+        GoalProgress {
+            value,
+            fraction,
+            reached: value >= self.target && !sessions.is_empty(),
+        }
+    }
+}
+
+/// Checks goals after a session was added and updates their state.
+///
+/// A goal is missed if its previous checked period is over and it wasn't
+/// reached, so misses are only noticed when a new session is added.
+pub fn check(goals: &mut [Goal], sessions: &[SavedSession], day_start: u32, today: i64) -> Vec<GoalEvent> {
+    let mut events = Vec::new();
+    for goal in goals.iter_mut() {
+        let current = goal.period.period_start(today);
+        if let Some(previous) = goal.checked_period.filter(|p| *p < current) {
+            if goal.reached_period != Some(previous) {
+                let progress = goal.progress(sessions, day_start, previous);
+                if progress.reached {
+                    goal.reached_period = Some(previous);
+                }
+                events.push(GoalEvent { goal: goal.clone(), progress, reached: progress.reached });
+            }
+        }
+        if goal.is_cumulative() && goal.reached_period != Some(current) {
+            let progress = goal.progress(sessions, day_start, current);
+            if progress.reached {
+                goal.reached_period = Some(current);
+                events.push(GoalEvent { goal: goal.clone(), progress, reached: true });
+            }
+        }
+        goal.checked_period = Some(current);
+    }
+
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{session, MONDAY};
+
+    #[test]
+    fn progress_without_sessions() {
+        let goal = Goal::new(GoalMetric::AvgScore, 0.0, Granularity::Week);
+        let progress = goal.progress(&[], 0, MONDAY);
+        assert_eq!(progress, GoalProgress { value: 0.0, fraction: 1.0, reached: false });
+    }
+
+    #[test]
+    fn progress_counts_period_only() {
+        let sessions = vec![
+            session(MONDAY - 1, 8, 10, 3),
+            session(MONDAY, 8, 10, 3),
+            session(MONDAY + 6, 8, 20, 5),
+            session(MONDAY + 7, 8, 10, 3),
+        ];
+        let goal = Goal::new(GoalMetric::Minutes, 60.0, Granularity::Week);
+        let progress = goal.progress(&sessions, 0, MONDAY);
+        assert_eq!(progress.value, 30.0);
+        assert_eq!(progress.fraction, 0.5);
+        assert!(!progress.reached);
+
+        let goal = Goal::new(GoalMetric::AvgScore, 4.0, Granularity::Week);
+        assert_eq!(goal.progress(&sessions, 0, MONDAY).value, 4.0);
+        assert!(goal.progress(&sessions, 0, MONDAY).reached);
+    }
+
+    #[test]
+    fn fraction_is_capped() {
+        let sessions = vec![session(MONDAY, 8, 10, 3), session(MONDAY, 9, 10, 3)];
+        let goal = Goal::new(GoalMetric::Sessions, 1.0, Granularity::Day);
+        let progress = goal.progress(&sessions, 0, MONDAY);
+        assert_eq!(progress.value, 2.0);
+        assert_eq!(progress.fraction, 1.0);
+    }
+
+    #[test]
+    fn progress_respects_day_start() {
+        // 01:00 belongs to the previous day when a day starts at 02:00
+        let sessions = vec![session(MONDAY + 1, 1, 10, 3)];
+        let goal = Goal::new(GoalMetric::Sessions, 1.0, Granularity::Day);
+        assert!(goal.progress(&sessions, 0, MONDAY + 1).reached);
+        assert!(goal.progress(&sessions, 2 * 60, MONDAY).reached);
+        assert!(!goal.progress(&sessions, 2 * 60, MONDAY + 1).reached);
+    }
+
+    #[test]
+    fn cumulative_goal_is_reached_once() {
+        let mut goals = vec![Goal::new(GoalMetric::Sessions, 2.0, Granularity::Day)];
+        let mut sessions = vec![session(MONDAY, 8, 10, 3)];
+        assert!(check(&mut goals, &sessions, 0, MONDAY).is_empty());
+
+        sessions.push(session(MONDAY, 9, 10, 3));
+        let events = check(&mut goals, &sessions, 0, MONDAY);
+        assert_eq!(events.len(), 1);
+        assert!(events[0].reached);
+        assert_eq!(goals[0].reached_period, Some(MONDAY));
+
+        sessions.push(session(MONDAY, 10, 10, 3));
+        assert!(check(&mut goals, &sessions, 0, MONDAY).is_empty());
+        // A reached period isn't reported as missed later
+        sessions.push(session(MONDAY + 1, 8, 10, 3));
+        assert!(check(&mut goals, &sessions, 0, MONDAY + 1).is_empty());
+    }
+
+    #[test]
+    fn missed_goal_is_noticed_in_a_later_period() {
+        let mut goals = vec![Goal::new(GoalMetric::Sessions, 2.0, Granularity::Day)];
+        let mut sessions = vec![session(MONDAY, 8, 10, 3)];
+        check(&mut goals, &sessions, 0, MONDAY);
+
+        // A missed day in between isn't checked
+        sessions.push(session(MONDAY + 2, 8, 10, 3));
+        let events = check(&mut goals, &sessions, 0, MONDAY + 2);
+        assert_eq!(events.len(), 1);
+        assert!(!events[0].reached);
+        assert_eq!(events[0].progress.value, 1.0);
+        assert_eq!(goals[0].checked_period, Some(MONDAY + 2));
+    }
+
+    #[test]
+    fn average_goal_is_judged_after_its_period() {
+        let mut goals = vec![Goal::new(GoalMetric::AvgScore, 4.0, Granularity::Week)];
+        let mut sessions = vec![session(MONDAY, 8, 10, 5)];
+        assert!(check(&mut goals, &sessions, 0, MONDAY).is_empty());
+
+        sessions.push(session(MONDAY + 6, 8, 10, 3));
+        assert!(check(&mut goals, &sessions, 0, MONDAY + 6).is_empty());
+
+        sessions.push(session(MONDAY + 7, 8, 10, 1));
+        let events = check(&mut goals, &sessions, 0, MONDAY + 7);
+        assert_eq!(events.len(), 1);
+        assert!(events[0].reached);
+        assert_eq!(events[0].progress.value, 4.0);
+        assert_eq!(goals[0].reached_period, Some(MONDAY));
+    }
+}
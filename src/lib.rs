@@ -1,8 +1,16 @@
 use wasm_bindgen::prelude::wasm_bindgen;
 
+mod achievements;
+mod analytics;
 mod app;
+mod calendar;
 mod db;
+mod goals;
 mod rsg;
+mod settings;
+mod stats;
+#[cfg(test)]
+mod test_util;
 
 use app::App;
 
@@ -1,6 +1,14 @@
+mod achievements;
+mod analytics;
 mod app;
+mod calendar;
 mod db;
+mod goals;
 mod rsg;
+mod settings;
+mod stats;
+#[cfg(test)]
+mod test_util;
 
 use app::App;
 
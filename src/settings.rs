@@ -0,0 +1,202 @@
+use serde::{Deserialize, Serialize};
+
+/// Optional lines drawn over daily averages
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct ChartOverlays {
+    pub rolling_mean: bool,
+    pub ewma: bool,
+    pub trend: bool,
+}
+
+/// Parameters of a synthesised tone
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Tone {
+    /// Fundamental frequency in Hz
+    pub pitch: f32,
+    /// Time to fade out in seconds
+    pub decay: f32,
+    /// Loudness of every overtone relative to the previous one (0 to 1)
+    pub harmonics: f32,
+}
+
+/// Synthesised tones played instead of recorded sounds
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SynthSettings {
+    pub enabled: bool,
+    /// Tone of intermediate bells
+    pub bell: Tone,
+    /// Tone of a final bowl strike
+    pub bowl: Tone,
+}
+
+impl Default for SynthSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bell: Tone { pitch: 880.0, decay: 3.0, harmonics: 0.5 },
+            bowl: Tone { pitch: 196.0, decay: 10.0, harmonics: 0.6 },
+        }
+    }
+}
+
+/// How session signals are given
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub enum SignalModality {
+    #[default]
+    Sound,
+    Vibration,
+    Both,
+    /// Neither sound nor vibration (for visual signals alone)
+    Silent,
+}
+
+impl SignalModality {
+    pub fn id(&self) -> &str {
+        match self {
+            Self::Sound => "sound",
+            Self::Vibration => "vibration",
+            Self::Both => "both",
+            Self::Silent => "silent",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Self {
+        match id {
+            "vibration" => Self::Vibration,
+            "both" => Self::Both,
+            "silent" => Self::Silent,
+            _ => Self::Sound,
+        }
+    }
+
+    pub fn has_sound(&self) -> bool {
+        matches!(self, Self::Sound | Self::Both)
+    }
+
+    pub fn has_vibration(&self) -> bool {
+        matches!(self, Self::Vibration | Self::Both)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub enum VisualStyle {
+    /// Soft colour pulse
+    #[default]
+    Pulse,
+    /// Short bright flash
+    Flash,
+}
+
+/// Full-screen visual cue shown on session signals
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub struct VisualSignal {
+    pub enabled: bool,
+    pub style: VisualStyle,
+    /// Opacity of a cue in percents
+    pub intensity: u32,
+    /// Replace animations with a slow fade if a system asks for reduced motion
+    pub reduced_motion: bool,
+}
+
+impl Default for VisualSignal {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            style: VisualStyle::default(),
+            intensity: 60,
+            reduced_motion: true,
+        }
+    }
+}
+
+/// Background sound played during a session
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub enum AmbientSound {
+    Rain,
+    BrownNoise,
+    Stream,
+}
+
+/// Background soundscape settings
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub struct AmbientSettings {
+    /// No background if not set
+    pub sound: Option<AmbientSound>,
+    /// Volume in percents (of the master volume)
+    pub volume: u32,
+}
+
+impl Default for AmbientSettings {
+    fn default() -> Self {
+        Self {
+            sound: None,
+            volume: 30,
+        }
+    }
+}
+
+/// Spoken announcements settings
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SpeechSettings {
+    pub enabled: bool,
+    /// Voice name (a browser default if empty)
+    pub voice: String,
+    /// Language tag like `en-US` (a browser default if empty)
+    pub lang: String,
+    /// Speech rate from 0.5 to 2
+    pub rate: f32,
+}
+
+impl Default for SpeechSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            voice: String::new(),
+            lang: String::new(),
+            rate: 1.0,
+        }
+    }
+}
+
+/// Gain of the last strike of a fading closing sequence
+const CLOSING_FADE_FLOOR: f64 = 0.25;
+
+/// Bowl strikes ending a session and a silent rest before rating it
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ClosingSequence {
+    /// Number of final sound strikes
+    pub strikes: u32,
+    /// Time between strikes in seconds
+    pub spacing: u32,
+    /// Whether each strike is quieter than the previous one
+    pub fade: bool,
+    /// Silence after the last strike before the rating prompt in seconds
+    pub rest: u32,
+}
+
+impl Default for ClosingSequence {
+    fn default() -> Self {
+        Self {
+            strikes: 1,
+            spacing: 5,
+            fade: false,
+            rest: 0,
+        }
+    }
+}
+
+impl ClosingSequence {
+    /// Returns a gain factor of a strike counted from 0.
+    pub fn strike_gain(&self, strike: u32) -> f64 {
+        if !self.fade || self.strikes < 2 {
+            return 1.0;
+        }
+        let step = (1.0 - CLOSING_FADE_FLOOR) / (self.strikes - 1) as f64;
+        1.0 - step * strike as f64
+    }
+
+    /// Time from the first strike to the rating prompt in seconds
+    pub fn length(&self) -> u32 {
+        self.strikes.saturating_sub(1) * self.spacing + self.rest
+    }
+}
@@ -0,0 +1,294 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::calendar::{civil_from_days, days_from_civil, weekday};
+use crate::db::SavedSession;
+
+/// Length of a time-of-day slot in hours
+pub const TIME_SLOT_HOURS: u32 = 3;
+
+/// Length of periods scores are aggregated over
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub enum Granularity {
+    #[default]
+    Day,
+    Week,
+    Month,
+}
+
+impl Granularity {
+    /// Returns the first day of a period a day belongs to.
+    pub fn period_start(&self, day: i64) -> i64 {
+        match self {
+            Self::Day => day,
+            Self::Week => day - weekday(day) as i64,
+            Self::Month => {
+                let (y, m, _) = civil_from_days(day);
+                days_from_civil(y, m, 1)
+            }
+        }
+    }
+
+    /// Returns the first day of a period following a period that starts with `start`.
+    pub fn next_period_start(&self, start: i64) -> i64 {
+        match self {
+            Self::Day => start + 1,
+            Self::Week => start + 7,
+            Self::Month => {
+                let (y, m, _) = civil_from_days(start);
+                if m == 12 { days_from_civil(y + 1, 1, 1) } else { days_from_civil(y, m + 1, 1) }
+            }
+        }
+    }
+
+    /// Approximate length of a period in days
+    pub fn days(&self) -> i64 {
+        match self {
+            Self::Day => 1,
+            Self::Week => 7,
+            Self::Month => 30,
+        }
+    }
+}
+
+/// Average score over a period (day, week or month)
+#[derive(Clone, Debug, PartialEq)]
+pub struct PeriodAvg {
+    /// Day number (days since 1970-01-01) of the first day of a period
+    pub day: i64,
+    pub avg: f32,
+    /// Number of sessions
+    pub count: usize,
+    /// Minimal session score
+    pub min: usize,
+    /// Maximal session score
+    pub max: usize,
+}
+
+/// Figures computed from a session log
+#[derive(Debug, Default, PartialEq)]
+pub struct Stats {
+    pub total_sessions: usize,
+    pub total_minutes: usize,
+    /// Consecutive days with sessions ending today (or yesterday)
+    pub current_streak: usize,
+    pub longest_streak: usize,
+    pub mean: Option<f32>,
+    pub mean_7_days: Option<f32>,
+    pub mean_30_days: Option<f32>,
+    /// Number of sessions for every score from 0 to 5
+    pub histogram: [usize; 6],
+    /// Start hour of a time-of-day slot with the best mean score and that score
+    pub best_time: Option<(u32, f32)>,
+}
+
+/// Computes statistics. `today` is a day number of the current "meditation day".
+pub fn compute(sessions: &[SavedSession], day_start: u32, today: i64) -> Stats {
+    let mut stats = Stats::default();
+    let mut days: Vec<i64> = Vec::new();
+    let mut slots: BTreeMap<u32, (usize, usize)> = BTreeMap::new();
+    for session in sessions {
+        stats.total_sessions += 1;
+        stats.total_minutes += session.duration;
+        stats.histogram[session.score.min(5)] += 1;
+        days.push(session.day(day_start));
+        let slot = session.minute_of_day() / 60 / TIME_SLOT_HOURS * TIME_SLOT_HOURS;
+        let entry = slots.entry(slot).or_insert((0, 0));
+        entry.0 += session.score;
+        entry.1 += 1;
+    }
+    days.sort();
+    days.dedup();
+
+    // Streaks
+    let mut streak = 0;
+    let mut previous: Option<i64> = None;
+    for day in &days {
+        streak = match previous {
+            Some(p) if p + 1 == *day => streak + 1,
+            _ => 1,
+        };
+        stats.longest_streak = stats.longest_streak.max(streak);
+        previous = Some(*day);
+    }
+    if let Some(last) = previous {
+        if last == today || last + 1 == today {
+            stats.current_streak = streak;
+        }
+    }
+
+    // Means
+    // `Option::is_none_or` would need a newer compiler
+    #[allow(clippy::unnecessary_map_or)]
+    let mean_since = |first_day: Option<i64>| {
+        let scores: Vec<usize> = sessions
+            .iter()
+            .filter(|s| first_day.map_or(true, |d| s.day(day_start) >= d))
+            .map(|s| s.score)
+            .collect();
+        mean(&scores)
+    };
+    stats.mean = mean_since(None);
+    stats.mean_7_days = mean_since(Some(today - 6));
+    stats.mean_30_days = mean_since(Some(today - 29));
+
+    stats.best_time = slots
+        .into_iter()
+        .map(|(slot, (total, count))| (slot, total as f32 / count as f32))
+        .fold(None, |best: Option<(u32, f32)>, (slot, avg)| match best {
+            Some((_, best_avg)) if best_avg >= avg => best,
+            _ => Some((slot, avg)),
+        });
+
+    stats
+}
+
+/// Computes average scores over periods in ascending order of periods.
+pub fn period_avgs(sessions: &[SavedSession], day_start: u32, granularity: Granularity) -> Vec<PeriodAvg> {
+    let mut periods: BTreeMap<i64, PeriodAvg> = BTreeMap::new();
+    for session in sessions {
+        let day = granularity.period_start(session.day(day_start));
+        let period = periods.entry(day).or_insert(PeriodAvg {
+            day,
+            avg: 0.0,
+            count: 0,
+            min: session.score,
+            max: session.score,
+        });
+        // Keep a total score in `avg` until all sessions are counted
+        period.avg += session.score as f32;
+        period.count += 1;
+        period.min = period.min.min(session.score);
+        period.max = period.max.max(session.score);
+    }
+
+    periods
+        .into_values()
+        .map(|period| PeriodAvg { avg: period.avg / period.count as f32, ..period })
+        .collect()
+}
+
+fn mean(scores: &[usize]) -> Option<f32> {
+    if scores.is_empty() {
+        None
+    } else {
+        Some(scores.iter().sum::<usize>() as f32 / scores.len() as f32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{session, MONDAY};
+
+    #[test]
+    fn no_sessions() {
+        assert_eq!(compute(&[], 0, MONDAY), Stats::default());
+        assert!(period_avgs(&[], 0, Granularity::Day).is_empty());
+    }
+
+    #[test]
+    fn single_session() {
+        let stats = compute(&[session(MONDAY, 8, 15, 4)], 0, MONDAY);
+        assert_eq!(stats.total_sessions, 1);
+        assert_eq!(stats.total_minutes, 15);
+        assert_eq!(stats.current_streak, 1);
+        assert_eq!(stats.longest_streak, 1);
+        assert_eq!(stats.mean, Some(4.0));
+        assert_eq!(stats.histogram, [0, 0, 0, 0, 1, 0]);
+        assert_eq!(stats.best_time, Some((6, 4.0)));
+    }
+
+    #[test]
+    fn streak_breaks_on_missed_day() {
+        let sessions: Vec<SavedSession> = [0, 1, 2, 4, 5]
+            .iter()
+            .map(|i| session(MONDAY + i, 8, 15, 3))
+            .collect();
+        let stats = compute(&sessions, 0, MONDAY + 5);
+        assert_eq!(stats.longest_streak, 3);
+        assert_eq!(stats.current_streak, 2);
+    }
+
+    #[test]
+    fn current_streak_ends_yesterday_at_latest() {
+        let sessions = vec![session(MONDAY, 8, 15, 3), session(MONDAY + 1, 8, 15, 3)];
+        assert_eq!(compute(&sessions, 0, MONDAY + 2).current_streak, 2);
+        assert_eq!(compute(&sessions, 0, MONDAY + 3).current_streak, 0);
+        assert_eq!(compute(&sessions, 0, MONDAY + 3).longest_streak, 2);
+    }
+
+    #[test]
+    fn streak_respects_day_start() {
+        // 01:00 belongs to the previous day when a day starts at 02:00
+        let sessions = vec![session(MONDAY, 20, 15, 3), session(MONDAY + 1, 1, 15, 3)];
+        assert_eq!(compute(&sessions, 0, MONDAY + 1).current_streak, 2);
+        assert_eq!(compute(&sessions, 2 * 60, MONDAY).current_streak, 1);
+    }
+
+    #[test]
+    fn recent_means_include_window_edges() {
+        let sessions = vec![
+            session(MONDAY - 30, 8, 15, 1),
+            session(MONDAY - 29, 8, 15, 2),
+            session(MONDAY - 7, 8, 15, 3),
+            session(MONDAY - 6, 8, 15, 5),
+        ];
+        let stats = compute(&sessions, 0, MONDAY);
+        assert_eq!(stats.mean_7_days, Some(5.0));
+        assert_eq!(stats.mean_30_days, Some(10.0 / 3.0));
+        assert_eq!(stats.mean, Some(11.0 / 4.0));
+    }
+
+    #[test]
+    fn best_time_slot() {
+        let sessions = vec![
+            session(MONDAY, 7, 15, 2),
+            session(MONDAY, 8, 15, 4),
+            session(MONDAY, 21, 15, 5),
+            session(MONDAY, 22, 15, 4),
+        ];
+        assert_eq!(compute(&sessions, 0, MONDAY).best_time, Some((21, 4.5)));
+    }
+
+    #[test]
+    fn period_starts() {
+        assert_eq!(Granularity::Day.period_start(MONDAY + 3), MONDAY + 3);
+        assert_eq!(Granularity::Week.period_start(MONDAY), MONDAY);
+        assert_eq!(Granularity::Week.period_start(MONDAY + 6), MONDAY);
+        assert_eq!(Granularity::Week.period_start(MONDAY + 7), MONDAY + 7);
+        // 2023-01-31 is in a month starting with 2023-01-01
+        assert_eq!(Granularity::Month.period_start(MONDAY + 29), MONDAY - 1);
+        assert_eq!(Granularity::Month.period_start(MONDAY + 30), MONDAY + 30);
+    }
+
+    #[test]
+    fn next_period_starts() {
+        assert_eq!(Granularity::Day.next_period_start(MONDAY), MONDAY + 1);
+        assert_eq!(Granularity::Week.next_period_start(MONDAY), MONDAY + 7);
+        assert_eq!(Granularity::Month.next_period_start(MONDAY - 1), MONDAY + 30);
+        // 2023-12-01 is followed by 2024-01-01
+        assert_eq!(Granularity::Month.next_period_start(19692), 19723);
+    }
+
+    #[test]
+    fn period_avgs_group_by_granularity() {
+        let sessions = vec![
+            session(MONDAY, 8, 15, 2),
+            session(MONDAY + 6, 23, 15, 4),
+            session(MONDAY + 7, 8, 15, 5),
+        ];
+        let weeks = period_avgs(&sessions, 0, Granularity::Week);
+        assert_eq!(weeks, vec![
+            PeriodAvg { day: MONDAY, avg: 3.0, count: 2, min: 2, max: 4 },
+            PeriodAvg { day: MONDAY + 7, avg: 5.0, count: 1, min: 5, max: 5 },
+        ]);
+        // Morning sessions belong to the previous day if a day starts at 22:00
+        let weeks = period_avgs(&sessions, 22 * 60, Granularity::Week);
+        assert_eq!(weeks[0].day, MONDAY - 7);
+        assert_eq!(weeks[0].count, 1);
+        assert_eq!(weeks[1].day, MONDAY);
+        assert_eq!(weeks[1].count, 2);
+    }
+}
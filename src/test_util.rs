@@ -0,0 +1,18 @@
+//! Fixtures shared by unit tests
+
+use crate::db::SavedSession;
+
+pub const DAY: i64 = 24 * 60 * 60;
+/// 2023-01-02
+pub const MONDAY: i64 = 19359;
+
+/// Returns a session started at `hour` (UTC) of a given day.
+pub fn session(day: i64, hour: i64, duration: usize, score: usize) -> SavedSession {
+    SavedSession {
+        ts: (day * DAY + hour * 60 * 60).to_string(),
+        duration,
+        score,
+        tz_offset: Some(0),
+        ..Default::default()
+    }
+}